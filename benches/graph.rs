@@ -0,0 +1,176 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Benchmarks for creating, validating and querying large component graphs.
+
+use component_graph::{
+    ComponentCategory, ComponentGraph, InverterType, SimpleComponent, SimpleConnection,
+};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+/// Returns the components and connections of a synthetic site with about
+/// `size` components.
+///
+/// The site has a grid meter with alternating PV and battery branches below
+/// it.  Each PV branch has a meter with two PV inverters, and each battery
+/// branch has a meter with two battery inverters sharing a battery.
+fn site(size: usize) -> (Vec<SimpleComponent>, Vec<SimpleConnection>) {
+    let mut components = vec![
+        SimpleComponent::new(1, ComponentCategory::Grid),
+        SimpleComponent::new(2, ComponentCategory::Meter),
+    ];
+    let mut connections = vec![SimpleConnection::new(1, 2)];
+
+    let mut next_id = 3;
+    let mut add = |category, predecessors: &[u64]| {
+        let id = next_id;
+        next_id += 1;
+        components.push(SimpleComponent::new(id, category));
+        connections.extend(predecessors.iter().map(|&p| SimpleConnection::new(p, id)));
+        id
+    };
+
+    for branch in 0..(size - 2).div_ceil(4) {
+        let meter = add(ComponentCategory::Meter, &[2]);
+        if branch % 2 == 0 {
+            add(ComponentCategory::Inverter(InverterType::Solar), &[meter]);
+            add(ComponentCategory::Inverter(InverterType::Solar), &[meter]);
+        } else {
+            let first = add(ComponentCategory::Inverter(InverterType::Battery), &[meter]);
+            let second = add(ComponentCategory::Inverter(InverterType::Battery), &[meter]);
+            add(ComponentCategory::Battery, &[first, second]);
+        }
+    }
+
+    (components, connections)
+}
+
+/// Returns the components and connections of a synthetic site with about
+/// `size` components, made of stacked diamonds of meters.
+///
+/// Each diamond is a meter with two meters below it, which both have the
+/// same meter below them.  There are exponentially many paths from the grid
+/// to the bottom of the stack, and each connection into the bottom of a
+/// diamond has to be checked for an indirect path.
+fn diamonds(size: usize) -> (Vec<SimpleComponent>, Vec<SimpleConnection>) {
+    let mut components = vec![
+        SimpleComponent::new(1, ComponentCategory::Grid),
+        SimpleComponent::new(2, ComponentCategory::Meter),
+    ];
+    let mut connections = vec![SimpleConnection::new(1, 2)];
+
+    let mut top = 2;
+    for _ in 0..(size - 2).div_ceil(3) {
+        let (left, right, bottom) = (top + 1, top + 2, top + 3);
+        for id in [left, right, bottom] {
+            components.push(SimpleComponent::new(id, ComponentCategory::Meter));
+        }
+        connections.extend([
+            SimpleConnection::new(top, left),
+            SimpleConnection::new(top, right),
+            SimpleConnection::new(left, bottom),
+            SimpleConnection::new(right, bottom),
+        ]);
+        top = bottom;
+    }
+
+    (components, connections)
+}
+
+fn bench_creation(c: &mut Criterion) {
+    for (name, generate) in [
+        ("try_new", site as fn(usize) -> _),
+        ("try_new_diamonds", diamonds),
+    ] {
+        let mut group = c.benchmark_group(name);
+        for size in SIZES {
+            let (components, connections) = generate(size);
+            group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+                b.iter_batched(
+                    || (components.clone(), connections.clone()),
+                    |(components, connections)| ComponentGraph::try_new(components, connections),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+        group.finish();
+    }
+}
+
+fn bench_validation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("revalidate");
+    for size in SIZES {
+        let (components, connections) = site(size);
+        let Ok(graph) = ComponentGraph::try_new(components, connections) else {
+            panic!("the synthetic site should be valid");
+        };
+        group.bench_with_input(BenchmarkId::from_parameter(size), &graph, |b, graph| {
+            b.iter(|| graph.revalidate())
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("revalidate_diamonds");
+    for size in SIZES {
+        let (components, connections) = diamonds(size);
+        let Ok(graph) = ComponentGraph::try_new(components, connections) else {
+            panic!("the stacked diamonds should be valid");
+        };
+        group.bench_with_input(BenchmarkId::from_parameter(size), &graph, |b, graph| {
+            b.iter(|| graph.revalidate())
+        });
+    }
+    group.finish();
+}
+
+fn bench_meter_roles(c: &mut Criterion) {
+    let mut group = c.benchmark_group("meter_roles");
+    for size in SIZES {
+        let (components, connections) = site(size);
+        let meters = components
+            .iter()
+            .filter(|c| c.category == ComponentCategory::Meter)
+            .map(|c| c.id)
+            .collect::<Vec<_>>();
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter_batched(
+                || ComponentGraph::try_new(components.clone(), connections.clone()),
+                |graph| {
+                    let Ok(graph) = graph else {
+                        panic!("the synthetic site should be valid");
+                    };
+                    for &meter in &meters {
+                        let _ = black_box(graph.meter_role(meter));
+                    }
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_lint(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lint");
+    for size in SIZES {
+        let (components, connections) = site(size);
+        let Ok(graph) = ComponentGraph::try_new(components, connections) else {
+            panic!("the synthetic site should be valid");
+        };
+        group.bench_with_input(BenchmarkId::from_parameter(size), &graph, |b, graph| {
+            b.iter(|| graph.lint())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_creation,
+    bench_validation,
+    bench_meter_roles,
+    bench_lint
+);
+criterion_main!(benches);
@@ -0,0 +1,275 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! This module defines the `ComponentGraphBuilder` struct, for constructing
+//! [`ComponentGraph`]s of [`SimpleComponent`]s programmatically.
+
+use crate::{
+    ComponentCategory, ComponentGraph, ComponentGraphConfig, Error, EvChargerType, InverterType,
+    SimpleComponent, SimpleConnection,
+};
+
+/// A handle to a component added to a [`ComponentGraphBuilder`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ComponentHandle(u64);
+
+impl ComponentHandle {
+    /// Returns the component ID of the component.
+    pub fn component_id(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A builder for [`ComponentGraph`]s of [`SimpleComponent`]s.
+///
+/// Components get consecutive IDs starting from `1`, in the order they are
+/// added.  For example, a site with a grid meter, a PV inverter and a battery
+/// can be built with:
+///
+/// ```
+/// use component_graph::builder::ComponentGraphBuilder;
+///
+/// let mut builder = ComponentGraphBuilder::new();
+/// let grid = builder.grid();
+/// let grid_meter = builder.meter();
+/// let pv_chain = builder.meter_pv_chain(1);
+/// let battery_chain = builder.meter_bat_chain(1, 1);
+/// builder
+///     .connect(grid, grid_meter)
+///     .connect(grid_meter, pv_chain)
+///     .connect(grid_meter, battery_chain);
+///
+/// let graph = builder.build()?;
+/// assert_eq!(graph.components().count(), 7);
+/// # Ok::<(), component_graph::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct ComponentGraphBuilder {
+    components: Vec<SimpleComponent>,
+    connections: Vec<SimpleConnection>,
+    next_id: u64,
+}
+
+impl Default for ComponentGraphBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ComponentGraphBuilder {
+    /// Creates a new, empty builder.
+    pub fn new() -> Self {
+        Self {
+            components: vec![],
+            connections: vec![],
+            next_id: 1,
+        }
+    }
+
+    /// Adds a component of the given `category`.
+    pub fn add_component(&mut self, category: ComponentCategory) -> ComponentHandle {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.components.push(SimpleComponent::new(id, category));
+        ComponentHandle(id)
+    }
+
+    /// Adds a connection from `source` to `destination`.
+    pub fn connect(&mut self, source: ComponentHandle, destination: ComponentHandle) -> &mut Self {
+        self.connections
+            .push(SimpleConnection::new(source.0, destination.0));
+        self
+    }
+
+    /// Adds a grid component.
+    pub fn grid(&mut self) -> ComponentHandle {
+        self.add_component(ComponentCategory::Grid)
+    }
+
+    /// Adds a meter.
+    pub fn meter(&mut self) -> ComponentHandle {
+        self.add_component(ComponentCategory::Meter)
+    }
+
+    /// Adds a battery.
+    pub fn battery(&mut self) -> ComponentHandle {
+        self.add_component(ComponentCategory::Battery)
+    }
+
+    /// Adds a battery inverter.
+    pub fn battery_inverter(&mut self) -> ComponentHandle {
+        self.add_component(ComponentCategory::Inverter(InverterType::Battery))
+    }
+
+    /// Adds a PV inverter.
+    pub fn solar_inverter(&mut self) -> ComponentHandle {
+        self.add_component(ComponentCategory::Inverter(InverterType::Solar))
+    }
+
+    /// Adds a wind inverter.
+    pub fn wind_inverter(&mut self) -> ComponentHandle {
+        self.add_component(ComponentCategory::Inverter(InverterType::Wind))
+    }
+
+    /// Adds an EV charger of the given type.
+    pub fn ev_charger(&mut self, charger_type: EvChargerType) -> ComponentHandle {
+        self.add_component(ComponentCategory::EvCharger(charger_type))
+    }
+
+    /// Adds a CHP.
+    pub fn chp(&mut self) -> ComponentHandle {
+        self.add_component(ComponentCategory::Chp)
+    }
+
+    /// Adds a meter with `num_inverters` battery inverters as successors,
+    /// each of which is connected to all of `num_batteries` batteries.
+    ///
+    /// Returns the meter.
+    pub fn meter_bat_chain(
+        &mut self,
+        num_inverters: usize,
+        num_batteries: usize,
+    ) -> ComponentHandle {
+        let meter = self.meter();
+        let inverters = (0..num_inverters)
+            .map(|_| self.battery_inverter())
+            .collect::<Vec<_>>();
+        let batteries = (0..num_batteries)
+            .map(|_| self.battery())
+            .collect::<Vec<_>>();
+        for &inverter in &inverters {
+            self.connect(meter, inverter);
+            for &battery in &batteries {
+                self.connect(inverter, battery);
+            }
+        }
+        meter
+    }
+
+    /// Adds a meter with `num_inverters` PV inverters as successors.
+    ///
+    /// Returns the meter.
+    pub fn meter_pv_chain(&mut self, num_inverters: usize) -> ComponentHandle {
+        self.meter_chain(num_inverters, Self::solar_inverter)
+    }
+
+    /// Adds a meter with `num_inverters` wind inverters as successors.
+    ///
+    /// Returns the meter.
+    pub fn meter_wind_chain(&mut self, num_inverters: usize) -> ComponentHandle {
+        self.meter_chain(num_inverters, Self::wind_inverter)
+    }
+
+    /// Adds a meter with `num_chargers` AC EV chargers as successors.
+    ///
+    /// Returns the meter.
+    pub fn meter_ev_charger_chain(&mut self, num_chargers: usize) -> ComponentHandle {
+        self.meter_chain(num_chargers, |b| b.ev_charger(EvChargerType::Ac))
+    }
+
+    /// Adds a meter with `num_chps` CHPs as successors.
+    ///
+    /// Returns the meter.
+    pub fn meter_chp_chain(&mut self, num_chps: usize) -> ComponentHandle {
+        self.meter_chain(num_chps, Self::chp)
+    }
+
+    /// Builds a [`ComponentGraph`] from the components and connections added
+    /// so far.
+    ///
+    /// The builder is left unchanged, so that it can be extended and built
+    /// again.  Returns an error if the graph is invalid.
+    pub fn build(&self) -> Result<ComponentGraph<SimpleComponent, SimpleConnection>, Error> {
+        self.build_with_config(ComponentGraphConfig::default())
+    }
+
+    /// Builds a [`ComponentGraph`] from the components and connections added
+    /// so far, validating it according to the given `config`.
+    ///
+    /// The builder is left unchanged, so that it can be extended and built
+    /// again.  Returns an error if the graph is invalid.
+    pub fn build_with_config(
+        &self,
+        config: ComponentGraphConfig,
+    ) -> Result<ComponentGraph<SimpleComponent, SimpleConnection>, Error> {
+        ComponentGraph::try_new_with_config(
+            self.components.clone(),
+            self.connections.clone(),
+            config,
+        )
+    }
+
+    /// Adds a meter with `count` successors, each added by `add`.
+    fn meter_chain(
+        &mut self,
+        count: usize,
+        add: impl Fn(&mut Self) -> ComponentHandle,
+    ) -> ComponentHandle {
+        let meter = self.meter();
+        for _ in 0..count {
+            let component = add(self);
+            self.connect(meter, component);
+        }
+        meter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    #[test]
+    fn test_builder() -> Result<(), Error> {
+        let mut builder = ComponentGraphBuilder::new();
+        let grid = builder.grid();
+        let grid_meter = builder.meter();
+        builder.connect(grid, grid_meter);
+
+        let bat_chain = builder.meter_bat_chain(2, 1);
+        let pv_chain = builder.meter_pv_chain(1);
+        let ev_chain = builder.meter_ev_charger_chain(1);
+        let chp_chain = builder.meter_chp_chain(1);
+        builder
+            .connect(grid_meter, bat_chain)
+            .connect(grid_meter, pv_chain)
+            .connect(grid_meter, ev_chain)
+            .connect(grid_meter, chp_chain);
+
+        let graph = builder.build()?;
+        assert_eq!(
+            graph.to_string(),
+            "\
+Grid:1
+└── Meter:2 (Grid meter)
+    ├── Meter:3 (Battery meter)
+    │   ├── BatteryInverter:4
+    │   │   └── Battery:6
+    │   └── BatteryInverter:5
+    │       └── Battery:6
+    ├── Meter:7 (PV meter)
+    │   └── SolarInverter:8
+    ├── Meter:9 (EVCharger meter)
+    │   └── ACEVCharger:10
+    └── Meter:11 (CHP meter)
+        └── CHP:12
+"
+        );
+
+        // The builder can be extended after building.
+        let battery = builder.battery();
+        builder.connect(grid_meter, battery);
+        let Err(err) = builder.build() else {
+            panic!("expected an error");
+        };
+        assert_eq!(err.kind(), ErrorKind::InvalidGraph);
+        assert!(builder
+            .build_with_config(ComponentGraphConfig {
+                allow_batteries_under_meters: true,
+                ..Default::default()
+            })
+            .is_ok());
+
+        Ok(())
+    }
+}
@@ -4,16 +4,18 @@
 //! This module defines the `ComponentCategory` enum, which represents the
 //! category of a component.
 
-use crate::graph_traits::Node;
-use std::fmt::Display;
+use crate::{graph_traits::Node, Error};
+use std::{fmt::Display, str::FromStr};
 
 /// Represents the type of an inverter.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InverterType {
     Unspecified,
     Solar,
     Battery,
     Hybrid,
+    Wind,
 }
 
 impl Display for InverterType {
@@ -23,6 +25,28 @@ impl Display for InverterType {
             InverterType::Solar => write!(f, "Solar"),
             InverterType::Battery => write!(f, "Battery"),
             InverterType::Hybrid => write!(f, "Hybrid"),
+            InverterType::Wind => write!(f, "Wind"),
+        }
+    }
+}
+
+/// Represents the type of an EV charger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EvChargerType {
+    Unspecified,
+    Ac,
+    Dc,
+    Hybrid,
+}
+
+impl Display for EvChargerType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvChargerType::Unspecified => write!(f, "Unspecified"),
+            EvChargerType::Ac => write!(f, "AC"),
+            EvChargerType::Dc => write!(f, "DC"),
+            EvChargerType::Hybrid => write!(f, "Hybrid"),
         }
     }
 }
@@ -32,14 +56,20 @@ impl Display for InverterType {
 /// Values of the underlying generated `ComponentCategory` and `ComponentType` types
 /// need to be converted to this type, so that they can be used in the
 /// `ComponentGraph`.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ComponentCategory {
     Unspecified,
     Grid,
     Meter,
     Battery,
     Inverter(InverterType),
-    EvCharger,
+    /// An EV charger.
+    ///
+    /// EV chargers whose type isn't known have the
+    /// [`Unspecified`][EvChargerType::Unspecified] type, and are displayed as
+    /// `EVCharger`.
+    EvCharger(EvChargerType),
     Converter,
     CryptoMiner,
     Electrolyzer,
@@ -49,6 +79,25 @@ pub enum ComponentCategory {
     VoltageTransformer,
     Hvac,
     Relay,
+    /// A sensor, like a grid-frequency or an irradiance sensor, which doesn't
+    /// take part in the power flow.
+    Sensor,
+    /// A PV string, or an MPPT tracker, beneath a solar or hybrid inverter.
+    ///
+    /// PV strings are only modeled for monitoring, and their power is already
+    /// included in the power of their inverter.
+    PvString,
+    /// A module of a battery, as listed by its battery management system.
+    ///
+    /// Battery modules are only modeled for monitoring, and their power is
+    /// already included in the power of their battery.
+    BatteryModule,
+    /// A category that this library doesn't know yet, with its number in the
+    /// upstream API.
+    ///
+    /// How components of these categories are treated is controlled by
+    /// [`ComponentGraphConfig::other_categories`][crate::ComponentGraphConfig::other_categories].
+    Other(u32),
 }
 
 impl Display for ComponentCategory {
@@ -59,7 +108,12 @@ impl Display for ComponentCategory {
             ComponentCategory::Meter => write!(f, "Meter"),
             ComponentCategory::Battery => write!(f, "Battery"),
             ComponentCategory::Inverter(inverter_type) => write!(f, "{}Inverter", inverter_type),
-            ComponentCategory::EvCharger => write!(f, "EVCharger"),
+            // EV chargers of unspecified type are shown as plain `EVCharger`,
+            // as they were before EV charger types were added.
+            ComponentCategory::EvCharger(EvChargerType::Unspecified) => write!(f, "EVCharger"),
+            ComponentCategory::EvCharger(ev_charger_type) => {
+                write!(f, "{}EVCharger", ev_charger_type)
+            }
             ComponentCategory::Converter => write!(f, "Converter"),
             ComponentCategory::CryptoMiner => write!(f, "CryptoMiner"),
             ComponentCategory::Electrolyzer => write!(f, "Electrolyzer"),
@@ -69,53 +123,320 @@ impl Display for ComponentCategory {
             ComponentCategory::VoltageTransformer => write!(f, "VoltageTransformer"),
             ComponentCategory::Hvac => write!(f, "HVAC"),
             ComponentCategory::Relay => write!(f, "Relay"),
+            ComponentCategory::Sensor => write!(f, "Sensor"),
+            ComponentCategory::PvString => write!(f, "PVString"),
+            ComponentCategory::BatteryModule => write!(f, "BatteryModule"),
+            ComponentCategory::Other(value) => write!(f, "Other({value})"),
         }
     }
 }
 
+/// Parses a category from its `Display` representation, like
+/// `"SolarInverter"`, `"ACEVCharger"`, `"Meter"` or `"Other(42)"`.
+impl FromStr for ComponentCategory {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(value) = s
+            .strip_prefix("Other(")
+            .and_then(|s| s.strip_suffix(')'))
+            .and_then(|s| s.parse().ok())
+        {
+            return Ok(ComponentCategory::Other(value));
+        }
+        let category = match s {
+            "Unspecified" => ComponentCategory::Unspecified,
+            "Grid" => ComponentCategory::Grid,
+            "Meter" => ComponentCategory::Meter,
+            "Battery" => ComponentCategory::Battery,
+            "UnspecifiedInverter" => ComponentCategory::Inverter(InverterType::Unspecified),
+            "SolarInverter" => ComponentCategory::Inverter(InverterType::Solar),
+            "BatteryInverter" => ComponentCategory::Inverter(InverterType::Battery),
+            "HybridInverter" => ComponentCategory::Inverter(InverterType::Hybrid),
+            "WindInverter" => ComponentCategory::Inverter(InverterType::Wind),
+            "EVCharger" | "UnspecifiedEVCharger" => {
+                ComponentCategory::EvCharger(EvChargerType::Unspecified)
+            }
+            "ACEVCharger" => ComponentCategory::EvCharger(EvChargerType::Ac),
+            "DCEVCharger" => ComponentCategory::EvCharger(EvChargerType::Dc),
+            "HybridEVCharger" => ComponentCategory::EvCharger(EvChargerType::Hybrid),
+            "Converter" => ComponentCategory::Converter,
+            "CryptoMiner" => ComponentCategory::CryptoMiner,
+            "Electrolyzer" => ComponentCategory::Electrolyzer,
+            "CHP" => ComponentCategory::Chp,
+            "Precharger" => ComponentCategory::Precharger,
+            "Fuse" => ComponentCategory::Fuse,
+            "VoltageTransformer" => ComponentCategory::VoltageTransformer,
+            "HVAC" => ComponentCategory::Hvac,
+            "Relay" => ComponentCategory::Relay,
+            "Sensor" => ComponentCategory::Sensor,
+            "PVString" => ComponentCategory::PvString,
+            "BatteryModule" => ComponentCategory::BatteryModule,
+            _ => {
+                return Err(Error::invalid_component(format!(
+                    "Unknown component category: {s}"
+                )))
+            }
+        };
+        Ok(category)
+    }
+}
+
+/// Category predicates.
+impl ComponentCategory {
+    /// Returns true if the category is unspecified.
+    pub fn is_unspecified(&self) -> bool {
+        *self == ComponentCategory::Unspecified
+    }
+
+    /// Returns true if this is the grid category.
+    pub fn is_grid(&self) -> bool {
+        *self == ComponentCategory::Grid
+    }
+
+    /// Returns true if this is the meter category.
+    pub fn is_meter(&self) -> bool {
+        *self == ComponentCategory::Meter
+    }
+
+    /// Returns true if this is the battery category.
+    pub fn is_battery(&self) -> bool {
+        *self == ComponentCategory::Battery
+    }
+
+    /// Returns true if this is an inverter category, of any type.
+    pub fn is_inverter(&self) -> bool {
+        matches!(self, ComponentCategory::Inverter(_))
+    }
+
+    /// Returns true if this is an inverter category with an unspecified type.
+    pub fn is_unspecified_inverter(&self) -> bool {
+        *self == ComponentCategory::Inverter(InverterType::Unspecified)
+    }
+
+    /// Returns true if this is the solar inverter category.
+    ///
+    /// Inverters with an unspecified type are not solar inverters.  To treat
+    /// them as the
+    /// [`unspecified_inverter_type`][crate::ComponentGraphConfig::unspecified_inverter_type]
+    /// of a config, check the
+    /// [`effective_category`][crate::ComponentGraphConfig::effective_category]
+    /// instead.  The same applies to the other inverter types.
+    pub fn is_pv_inverter(&self) -> bool {
+        *self == ComponentCategory::Inverter(InverterType::Solar)
+    }
+
+    /// Returns true if this is the battery inverter category.
+    pub fn is_battery_inverter(&self) -> bool {
+        *self == ComponentCategory::Inverter(InverterType::Battery)
+    }
+
+    /// Returns true if this is the hybrid inverter category.
+    pub fn is_hybrid_inverter(&self) -> bool {
+        *self == ComponentCategory::Inverter(InverterType::Hybrid)
+    }
+
+    /// Returns true if this is the wind inverter category.
+    pub fn is_wind_inverter(&self) -> bool {
+        *self == ComponentCategory::Inverter(InverterType::Wind)
+    }
+
+    /// Returns true if this is an EV charger category, of any type.
+    pub fn is_ev_charger(&self) -> bool {
+        matches!(self, ComponentCategory::EvCharger(_))
+    }
+
+    /// Returns true if this is the DC EV charger category.
+    pub fn is_dc_ev_charger(&self) -> bool {
+        *self == ComponentCategory::EvCharger(EvChargerType::Dc)
+    }
+
+    /// Returns true if this is the CHP category.
+    pub fn is_chp(&self) -> bool {
+        *self == ComponentCategory::Chp
+    }
+
+    /// Returns true if this is the sensor category.
+    pub fn is_sensor(&self) -> bool {
+        *self == ComponentCategory::Sensor
+    }
+
+    /// Returns true if this is the PV string category.
+    pub fn is_pv_string(&self) -> bool {
+        *self == ComponentCategory::PvString
+    }
+
+    /// Returns true if this is the battery module category.
+    pub fn is_battery_module(&self) -> bool {
+        *self == ComponentCategory::BatteryModule
+    }
+
+    /// Returns true if this is the fuse category.
+    pub fn is_fuse(&self) -> bool {
+        *self == ComponentCategory::Fuse
+    }
+
+    /// Returns true if this is a category unknown to this library.
+    pub fn is_other(&self) -> bool {
+        matches!(self, ComponentCategory::Other(_))
+    }
+}
+
 /// Predicates for checking the component category of a `Node`.
 pub(crate) trait CategoryPredicates: Node {
     fn is_unspecified(&self) -> bool {
-        self.category() == ComponentCategory::Unspecified
+        self.category().is_unspecified()
     }
 
     fn is_grid(&self) -> bool {
-        self.category() == ComponentCategory::Grid
+        self.category().is_grid()
     }
 
     fn is_meter(&self) -> bool {
-        self.category() == ComponentCategory::Meter
+        self.category().is_meter()
     }
 
     fn is_battery(&self) -> bool {
-        self.category() == ComponentCategory::Battery
+        self.category().is_battery()
     }
 
     fn is_inverter(&self) -> bool {
-        matches!(self.category(), ComponentCategory::Inverter(_))
+        self.category().is_inverter()
     }
 
-    fn is_battery_inverter(&self) -> bool {
-        self.category() == ComponentCategory::Inverter(InverterType::Battery)
+    fn is_unspecified_inverter(&self) -> bool {
+        self.category().is_unspecified_inverter()
     }
 
-    fn is_pv_inverter(&self) -> bool {
-        self.category() == ComponentCategory::Inverter(InverterType::Solar)
+    fn is_ev_charger(&self) -> bool {
+        self.category().is_ev_charger()
     }
 
-    fn is_unspecified_inverter(&self) -> bool {
-        self.category() == ComponentCategory::Inverter(InverterType::Unspecified)
+    fn is_dc_ev_charger(&self) -> bool {
+        self.category().is_dc_ev_charger()
     }
 
-    fn is_ev_charger(&self) -> bool {
-        self.category() == ComponentCategory::EvCharger
+    fn is_chp(&self) -> bool {
+        self.category().is_chp()
     }
 
-    fn is_chp(&self) -> bool {
-        self.category() == ComponentCategory::Chp
+    fn is_sensor(&self) -> bool {
+        self.category().is_sensor()
+    }
+
+    fn is_pv_string(&self) -> bool {
+        self.category().is_pv_string()
+    }
+
+    fn is_battery_module(&self) -> bool {
+        self.category().is_battery_module()
+    }
+
+    fn is_other(&self) -> bool {
+        self.category().is_other()
     }
 }
 
 /// Implement the `CategoryPredicates` trait for all types that implement the
 /// `Node` trait.
 impl<T: Node> CategoryPredicates for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ComponentGraphConfig;
+
+    #[test]
+    fn test_category_from_str() {
+        let categories = [
+            ComponentCategory::Unspecified,
+            ComponentCategory::Grid,
+            ComponentCategory::Meter,
+            ComponentCategory::Battery,
+            ComponentCategory::Inverter(InverterType::Unspecified),
+            ComponentCategory::Inverter(InverterType::Solar),
+            ComponentCategory::Inverter(InverterType::Battery),
+            ComponentCategory::Inverter(InverterType::Hybrid),
+            ComponentCategory::Inverter(InverterType::Wind),
+            ComponentCategory::EvCharger(EvChargerType::Unspecified),
+            ComponentCategory::EvCharger(EvChargerType::Ac),
+            ComponentCategory::EvCharger(EvChargerType::Dc),
+            ComponentCategory::EvCharger(EvChargerType::Hybrid),
+            ComponentCategory::Converter,
+            ComponentCategory::CryptoMiner,
+            ComponentCategory::Electrolyzer,
+            ComponentCategory::Chp,
+            ComponentCategory::Precharger,
+            ComponentCategory::Fuse,
+            ComponentCategory::VoltageTransformer,
+            ComponentCategory::Hvac,
+            ComponentCategory::Relay,
+            ComponentCategory::Sensor,
+            ComponentCategory::PvString,
+            ComponentCategory::BatteryModule,
+            ComponentCategory::Other(42),
+        ];
+        for category in categories {
+            assert_eq!(category.to_string().parse(), Ok(category));
+        }
+
+        assert_eq!(
+            ComponentCategory::EvCharger(EvChargerType::Unspecified).to_string(),
+            "EVCharger"
+        );
+        assert_eq!(
+            "UnspecifiedEVCharger".parse(),
+            Ok(ComponentCategory::EvCharger(EvChargerType::Unspecified))
+        );
+
+        assert_eq!(
+            "Windmill".parse::<ComponentCategory>(),
+            Err(Error::invalid_component(
+                "Unknown component category: Windmill"
+            ))
+        );
+        assert_eq!(
+            "Other(x)".parse::<ComponentCategory>(),
+            Err(Error::invalid_component(
+                "Unknown component category: Other(x)"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_category_predicates() {
+        assert!(ComponentCategory::Grid.is_grid());
+        assert!(!ComponentCategory::Meter.is_grid());
+        assert!(ComponentCategory::Inverter(InverterType::Solar).is_inverter());
+        assert!(!ComponentCategory::Inverter(InverterType::Solar).is_unspecified_inverter());
+        assert!(ComponentCategory::Inverter(InverterType::Unspecified).is_unspecified_inverter());
+        assert!(ComponentCategory::Inverter(InverterType::Solar).is_pv_inverter());
+        assert!(!ComponentCategory::Inverter(InverterType::Solar).is_battery_inverter());
+        assert!(ComponentCategory::Inverter(InverterType::Battery).is_battery_inverter());
+        assert!(ComponentCategory::Inverter(InverterType::Hybrid).is_hybrid_inverter());
+        assert!(!ComponentCategory::Inverter(InverterType::Hybrid).is_pv_inverter());
+        assert!(ComponentCategory::Inverter(InverterType::Wind).is_wind_inverter());
+        assert!(!ComponentCategory::Battery.is_battery_inverter());
+        assert!(!ComponentCategory::Inverter(InverterType::Unspecified).is_battery_inverter());
+
+        // Unspecified inverters are checked as the configured type.
+        let unspecified = ComponentCategory::Inverter(InverterType::Unspecified);
+        let config = ComponentGraphConfig {
+            allow_unspecified_inverters: true,
+            unspecified_inverter_type: InverterType::Solar,
+            ..Default::default()
+        };
+        assert!(config.effective_category(unspecified).is_pv_inverter());
+        assert!(!ComponentGraphConfig::default()
+            .effective_category(unspecified)
+            .is_battery_inverter());
+
+        assert!(ComponentCategory::EvCharger(EvChargerType::Ac).is_ev_charger());
+        assert!(!ComponentCategory::EvCharger(EvChargerType::Ac).is_dc_ev_charger());
+        assert!(ComponentCategory::EvCharger(EvChargerType::Dc).is_dc_ev_charger());
+        assert!(!ComponentCategory::Battery.is_battery_module());
+        assert!(ComponentCategory::BatteryModule.is_battery_module());
+        assert!(ComponentCategory::Other(42).is_other());
+        assert!(!ComponentCategory::Hvac.is_other());
+    }
+}
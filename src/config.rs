@@ -0,0 +1,321 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! This module defines the `ComponentGraphConfig` struct, which holds the
+//! options that control how a [`ComponentGraph`][crate::ComponentGraph] is
+//! validated.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{ComponentCategory, InverterType};
+
+/// How components of categories unknown to this library, i.e.
+/// [`ComponentCategory::Other`], are treated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OtherCategoryPolicy {
+    /// Graphs with such components are rejected.
+    #[default]
+    Reject,
+    /// Such components are accepted, and have no rules of their own, so they
+    /// can have any predecessors and successors.
+    ///
+    /// The rules of their neighbors still apply, though, so that, e.g., a
+    /// solar inverter under such a component is rejected, because inverters
+    /// must have a meter or the grid as their predecessor.
+    PassThrough,
+    /// Such components are accepted, but must be leaves.
+    LeafOnly,
+}
+
+/// Configuration options for creating and validating a
+/// [`ComponentGraph`][crate::ComponentGraph].
+///
+/// The default configuration applies all validation rules.
+///
+/// With the `serde` feature, the configuration can be serialized and
+/// deserialized, so that per-site policies can be loaded from files.  Options
+/// missing from the input take their default values.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ComponentGraphConfig {
+    /// The levels of the graph at which the successors of a component must be
+    /// exclusive to it, i.e., must not have any other predecessors.
+    ///
+    /// The grid component is at level `0`, its successors are at level `1`,
+    /// and so on.  A component reachable over paths of different lengths is
+    /// at the level of the shortest one.
+    ///
+    /// Defaults to `{0}`, which forbids diamond topologies directly beneath
+    /// the grid, but allows them deeper down.  An empty set disables the
+    /// check, so that ring or bus configurations can be represented.
+    pub exclusive_successor_levels: BTreeSet<usize>,
+
+    /// Whether batteries can be direct successors of meters.
+    ///
+    /// Some legacy sites register their batteries directly under a meter,
+    /// without a battery inverter in between.  When this is `true`, such
+    /// batteries are accepted, and meters with only batteries as successors
+    /// are detected as battery meters.
+    pub allow_batteries_under_meters: bool,
+
+    /// Whether to apply the validation rules that depend on the type of EV
+    /// chargers.
+    ///
+    /// When this is `true`, DC EV chargers must be the only successor of a
+    /// meter, so that their consumption is metered separately.  AC EV
+    /// chargers can share a meter with other components.
+    pub validate_ev_charger_types: bool,
+
+    /// Whether converters are transparent.
+    ///
+    /// When this is `true`, converters can be placed between a meter and the
+    /// components it measures, and are skipped when identifying the role of
+    /// the meter.  For example, a meter whose successors are converters with
+    /// only PV inverters as successors is identified as a PV meter.
+    pub transparent_converters: bool,
+
+    /// Whether voltage transformers are transparent.
+    ///
+    /// This works like [`transparent_converters`][Self::transparent_converters],
+    /// but for voltage transformers.
+    pub transparent_voltage_transformers: bool,
+
+    /// Whether inverters with an unspecified type are allowed.
+    ///
+    /// When this is `true`, such inverters are validated and classified as
+    /// if they were of the type given by
+    /// [`unspecified_inverter_type`][Self::unspecified_inverter_type].
+    /// Otherwise, they are rejected.
+    pub allow_unspecified_inverters: bool,
+
+    /// The type assumed for inverters with an unspecified type, when
+    /// [`allow_unspecified_inverters`][Self::allow_unspecified_inverters] is
+    /// `true`.
+    ///
+    /// Defaults to [`InverterType::Battery`].
+    pub unspecified_inverter_type: InverterType,
+
+    /// Whether to attach a snippet of the neighborhood of the offending
+    /// component to `InvalidGraph` validation errors.
+    ///
+    /// The snippet is a Mermaid flowchart of the component with its
+    /// predecessors and successors, available from
+    /// [`Error::snippet`][crate::Error::snippet].
+    pub include_error_snippets: bool,
+
+    /// The categories of successors allowed for components of a category,
+    /// replacing the built-in rules for that category.
+    ///
+    /// An empty list requires the components to be leaves.  Rules for
+    /// battery inverters also replace the requirement that they have a
+    /// battery as a successor.  Components of the allowed categories also
+    /// accept components of the given category as predecessors.  For
+    /// example, mapping
+    /// `ComponentCategory::EvCharger(EvChargerType::Ac)` to
+    /// `[ComponentCategory::Meter]` permits submeters beneath AC EV chargers.
+    ///
+    /// Only the categories with built-in leaf or successor rules can be
+    /// overridden: inverters, batteries, battery modules, PV strings, EV
+    /// chargers, CHPs and sensors.
+    ///
+    /// With the `serde` feature, this is represented as a map from category
+    /// names to lists of category names, like
+    /// `{ "ACEVCharger": ["Meter"] }`.
+    #[cfg_attr(feature = "serde", serde(with = "successor_rules_serde"))]
+    pub successor_rules: HashMap<ComponentCategory, Vec<ComponentCategory>>,
+
+    /// How components of categories unknown to this library are treated.
+    ///
+    /// Defaults to [`OtherCategoryPolicy::Reject`].  Other policies let
+    /// graphs be created from upstream data with new categories, before this
+    /// library is updated to support them.
+    pub other_categories: OtherCategoryPolicy,
+}
+
+impl Default for ComponentGraphConfig {
+    fn default() -> Self {
+        Self {
+            exclusive_successor_levels: BTreeSet::from([0]),
+            allow_batteries_under_meters: false,
+            validate_ev_charger_types: false,
+            transparent_converters: false,
+            transparent_voltage_transformers: false,
+            allow_unspecified_inverters: false,
+            unspecified_inverter_type: InverterType::Battery,
+            include_error_snippets: false,
+            successor_rules: HashMap::new(),
+            other_categories: OtherCategoryPolicy::Reject,
+        }
+    }
+}
+
+impl ComponentGraphConfig {
+    /// Returns the category that components of the given category are
+    /// treated as.
+    ///
+    /// This is the given category itself, except for inverters with an
+    /// unspecified type, when they are allowed, which are treated as inverters
+    /// of the [`unspecified_inverter_type`][Self::unspecified_inverter_type].
+    pub fn effective_category(&self, category: ComponentCategory) -> ComponentCategory {
+        match category {
+            ComponentCategory::Inverter(InverterType::Unspecified)
+                if self.allow_unspecified_inverters =>
+            {
+                ComponentCategory::Inverter(self.unspecified_inverter_type)
+            }
+            category => category,
+        }
+    }
+
+    /// Returns true if components of the given category are configured to be
+    /// transparent.
+    pub(crate) fn is_transparent(&self, category: ComponentCategory) -> bool {
+        match category {
+            ComponentCategory::Converter => self.transparent_converters,
+            ComponentCategory::VoltageTransformer => self.transparent_voltage_transformers,
+            _ => false,
+        }
+    }
+
+    /// Returns the categories of successors allowed for components of the
+    /// given category, from the configured
+    /// [`successor_rules`][Self::successor_rules], or `builtin` if there is
+    /// no rule for the category.
+    pub(crate) fn allowed_successors<'a>(
+        &'a self,
+        category: ComponentCategory,
+        builtin: &'a [ComponentCategory],
+    ) -> &'a [ComponentCategory] {
+        self.successor_rules
+            .get(&category)
+            .map_or(builtin, Vec::as_slice)
+    }
+
+    /// Returns the categories that the configured
+    /// [`successor_rules`][Self::successor_rules] allow to have components of
+    /// the given category as successors, ordered by their names.
+    pub(crate) fn extra_predecessors(&self, category: ComponentCategory) -> Vec<ComponentCategory> {
+        let mut categories = self
+            .successor_rules
+            .iter()
+            .filter(|(_, allowed)| allowed.contains(&category))
+            .map(|(&predecessor, _)| predecessor)
+            .collect::<Vec<_>>();
+        categories.sort_by_key(|c| c.to_string());
+        categories
+    }
+
+    /// Returns the categories of the components that are configured to be
+    /// transparent.
+    pub(crate) fn transparent_categories(&self) -> Vec<ComponentCategory> {
+        [
+            ComponentCategory::Converter,
+            ComponentCategory::VoltageTransformer,
+        ]
+        .into_iter()
+        .filter(|c| self.is_transparent(*c))
+        .collect()
+    }
+}
+
+/// (De)serialization of [`ComponentGraphConfig::successor_rules`], with the
+/// names of the categories, because JSON map keys must be strings.
+#[cfg(feature = "serde")]
+mod successor_rules_serde {
+    use std::collections::{BTreeMap, HashMap};
+
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::ComponentCategory;
+
+    pub(super) fn serialize<S: Serializer>(
+        rules: &HashMap<ComponentCategory, Vec<ComponentCategory>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        rules
+            .iter()
+            .map(|(category, allowed)| {
+                (
+                    category.to_string(),
+                    allowed.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<BTreeMap<_, _>>()
+            .serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<ComponentCategory, Vec<ComponentCategory>>, D::Error> {
+        let parse = |name: &str| name.parse::<ComponentCategory>().map_err(D::Error::custom);
+        BTreeMap::<String, Vec<String>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(category, allowed)| {
+                Ok((
+                    parse(&category)?,
+                    allowed.iter().map(|c| parse(c)).collect::<Result<_, _>>()?,
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::EvChargerType;
+
+    #[test]
+    fn test_deserialize() -> Result<(), serde_json::Error> {
+        let config: ComponentGraphConfig = serde_json::from_str("{}")?;
+        assert_eq!(config, ComponentGraphConfig::default());
+
+        let config: ComponentGraphConfig = serde_json::from_str(
+            r#"{
+                "exclusive_successor_levels": [],
+                "transparent_converters": true
+            }"#,
+        )?;
+        assert_eq!(
+            config,
+            ComponentGraphConfig {
+                exclusive_successor_levels: BTreeSet::new(),
+                transparent_converters: true,
+                ..Default::default()
+            }
+        );
+
+        let json = serde_json::to_string(&config)?;
+        assert_eq!(serde_json::from_str::<ComponentGraphConfig>(&json)?, config);
+
+        let config: ComponentGraphConfig = serde_json::from_str(
+            r#"{
+                "successor_rules": { "ACEVCharger": ["Meter"], "CHP": [] }
+            }"#,
+        )?;
+        assert_eq!(
+            config.successor_rules,
+            HashMap::from([
+                (
+                    ComponentCategory::EvCharger(EvChargerType::Ac),
+                    vec![ComponentCategory::Meter]
+                ),
+                (ComponentCategory::Chp, vec![]),
+            ])
+        );
+        let json = serde_json::to_string(&config)?;
+        assert_eq!(serde_json::from_str::<ComponentGraphConfig>(&json)?, config);
+
+        assert!(serde_json::from_str::<ComponentGraphConfig>(
+            r#"{ "successor_rules": { "Toaster": [] } }"#
+        )
+        .is_err());
+
+        Ok(())
+    }
+}
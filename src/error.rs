@@ -4,15 +4,19 @@
 //! This module defines the `Error` struct and the `ErrorKind` enum, which are
 //! used to represent errors that can occur in the library.
 
+#[cfg(feature = "miette")]
+mod diagnostic;
+
 /// A macro for defining the `ErrorKind` enum, the `Display` implementation for
 /// it, and the constructors for the `Error` struct.
 macro_rules! ErrorKind {
     ($(
+        $(#[$ctor_attr:meta])*
         ($kind:ident, $ctor:ident)
     ),*) => {
         /// The kind of error that occurred.
-        #[derive(Debug, PartialEq)]
-        pub(crate) enum ErrorKind {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum ErrorKind {
             $(
                 $kind,
             )*
@@ -36,10 +40,16 @@ macro_rules! ErrorKind {
                     stringify!($kind),
                     "` kind and the given description."
                 )]
+                $(#[$ctor_attr])*
                 pub(crate) fn $ctor(desc: impl Into<String>) -> crate::Error {
                     Self {
                         kind: ErrorKind::$kind,
                         desc: desc.into(),
+                        component_ids: Box::new([]),
+                        connection: None,
+                        rule: None,
+                        snippet: None,
+                        errors: Box::new([]),
                     }
                 }
             )*
@@ -48,19 +58,152 @@ macro_rules! ErrorKind {
 }
 
 ErrorKind!(
+    (Assumption, assumption),
     (ComponentNotFound, component_not_found),
     (Internal, internal),
     (InvalidComponent, invalid_component),
     (InvalidConnection, invalid_connection),
-    (InvalidGraph, invalid_graph)
+    (InvalidGraph, invalid_graph),
+    (Lint, lint),
+    (PhaseImbalance, phase_imbalance),
+    (RatedCurrentExceeded, rated_current_exceeded),
+    (RatedPowerExceeded, rated_power_exceeded),
+    (RedundantConnection, redundant_connection),
+    // Only raised by the conversions of the `serde` and `csv` features.
+    #[cfg_attr(not(any(feature = "serde", feature = "csv")), allow(dead_code))]
+    (Serialization, serialization)
 );
 
 /// An error that can occur during the creation or traversal of a
 /// [ComponentGraph][crate::ComponentGraph].
-#[derive(Debug, PartialEq)]
+///
+/// Besides its [`kind`][Error::kind] and description, an error carries the
+/// context it was raised in, like the IDs of the offending components, so
+/// that callers can handle it without parsing the description.
+///
+/// Two errors are equal if they have the same kind and description.
+#[derive(Clone, Debug)]
 pub struct Error {
     kind: ErrorKind,
     desc: String,
+    component_ids: Box<[u64]>,
+    connection: Option<(u64, u64)>,
+    rule: Option<Box<str>>,
+    snippet: Option<Box<str>>,
+    errors: Box<[Error]>,
+}
+
+/// Accessors for the context of an [`Error`].
+impl Error {
+    /// Returns the kind of the error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the description of the error.
+    pub fn description(&self) -> &str {
+        &self.desc
+    }
+
+    /// Returns the IDs of the components involved in the error.
+    ///
+    /// The component the error is about comes first, followed by the
+    /// neighbors that caused it, if any.
+    pub fn component_ids(&self) -> &[u64] {
+        &self.component_ids
+    }
+
+    /// Returns the `(source, destination)` component IDs of the connection
+    /// the error is about, if any.
+    pub fn connection(&self) -> Option<(u64, u64)> {
+        self.connection
+    }
+
+    /// Returns the name of the validation rule that raised the error, if
+    /// any.
+    ///
+    /// Validation failures are named after the validation step and the check
+    /// that failed, like `validate_batteries::leaf`, so that they can be
+    /// referenced without matching on their descriptions.
+    pub fn rule(&self) -> Option<&str> {
+        self.rule.as_deref()
+    }
+
+    /// Returns a Mermaid flowchart of the neighborhood of the offending
+    /// component, if one was attached.
+    ///
+    /// Snippets are only attached to `InvalidGraph` errors from validation,
+    /// when [`include_error_snippets`][crate::ComponentGraphConfig::include_error_snippets]
+    /// is enabled.
+    pub fn snippet(&self) -> Option<&str> {
+        self.snippet.as_deref()
+    }
+
+    /// Returns the individual errors that this error combines.
+    ///
+    /// This is empty unless the error was created from multiple validation
+    /// failures, with [`ValidationReport::into_result`][crate::ValidationReport::into_result].
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    /// Creates an `InvalidGraph` error that combines the given validation
+    /// failures.
+    ///
+    /// The description lists the descriptions of all the failures, one per
+    /// line.
+    pub(crate) fn multiple(errors: Vec<Error>) -> Self {
+        let mut err = Self::invalid_graph(format!(
+            "Multiple validation failures:\n    {}",
+            errors
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join("\n    ")
+        ));
+        err.errors = errors.into_boxed_slice();
+        err
+    }
+
+    /// Sets the IDs of the components involved in the error.
+    pub(crate) fn with_components(mut self, component_ids: impl IntoIterator<Item = u64>) -> Self {
+        self.component_ids = component_ids.into_iter().collect();
+        self
+    }
+
+    /// Sets the connection the error is about.
+    pub(crate) fn with_connection(mut self, source: u64, destination: u64) -> Self {
+        self.connection = Some((source, destination));
+        self
+    }
+
+    /// Sets the name of the validation rule that raised the error.
+    pub(crate) fn with_rule(mut self, rule: &'static str) -> Self {
+        self.rule = Some(rule.into());
+        self
+    }
+
+    /// Prefixes the rule of the error with the given `scope`, or sets the
+    /// rule to the `scope` if the error has none.
+    pub(crate) fn in_rule_scope(mut self, scope: &str) -> Self {
+        self.rule = Some(match self.rule {
+            Some(rule) => format!("{scope}::{rule}").into(),
+            None => scope.into(),
+        });
+        self
+    }
+
+    /// Sets the snippet of the neighborhood of the offending component.
+    pub(crate) fn with_snippet(mut self, snippet: String) -> Self {
+        self.snippet = Some(snippet.into_boxed_str());
+        self
+    }
+}
+
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.desc == other.desc
+    }
 }
 
 impl std::fmt::Display for Error {
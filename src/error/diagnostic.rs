@@ -0,0 +1,144 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! An implementation of [`miette::Diagnostic`] for [`Error`], for rendering
+//! errors and validation findings in terminals.
+
+use miette::{Diagnostic, LabeledSpan, Severity, SourceCode};
+
+use super::{Error, ErrorKind};
+
+impl Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        Some(match &self.rule {
+            Some(rule) => Box::new(format!("{}::{}", self.kind, rule)),
+            None => Box::new(self.kind),
+        })
+    }
+
+    fn severity(&self) -> Option<Severity> {
+        match self.kind {
+            ErrorKind::Assumption
+            | ErrorKind::Lint
+            | ErrorKind::PhaseImbalance
+            | ErrorKind::RatedCurrentExceeded
+            | ErrorKind::RatedPowerExceeded
+            | ErrorKind::RedundantConnection => Some(Severity::Warning),
+            _ => Some(Severity::Error),
+        }
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.desc)
+    }
+
+    /// Labels the places in the description where the offending components
+    /// are mentioned.
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let labels = self
+            .component_ids
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &cid)| {
+                let (offset, len) = find_component(&self.desc, cid)?;
+                let label = if index == 0 {
+                    format!("component {cid}")
+                } else {
+                    format!("related component {cid}")
+                };
+                Some(LabeledSpan::new_with_span(Some(label), (offset, len)))
+            })
+            .collect::<Vec<_>>();
+        if labels.is_empty() {
+            return None;
+        }
+        Some(Box::new(labels.into_iter()))
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        if self.errors.is_empty() {
+            return None;
+        }
+        Some(Box::new(self.errors.iter().map(|e| e as &dyn Diagnostic)))
+    }
+}
+
+/// Returns the offset and length of the first mention of the given component
+/// in a description.
+///
+/// Mentions of the form `Category:id` are preferred, and are returned whole.
+/// Otherwise, the first occurrence of the ID as a separate number is returned.
+fn find_component(desc: &str, cid: u64) -> Option<(usize, usize)> {
+    let id = cid.to_string();
+    let is_digit_at = |pos: usize| desc.as_bytes().get(pos).is_some_and(u8::is_ascii_digit);
+
+    let mut fallback = None;
+    for (offset, _) in desc.match_indices(&id) {
+        let end = offset + id.len();
+        if (offset > 0 && is_digit_at(offset - 1)) || is_digit_at(end) {
+            continue;
+        }
+        let category_len = desc[..offset]
+            .strip_suffix(':')
+            .map(|prefix| {
+                prefix.len()
+                    - prefix
+                        .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+                        .len()
+            })
+            .unwrap_or(0);
+        if category_len > 0 {
+            let start = offset - category_len - 1;
+            return Some((start, end - start));
+        }
+        fallback.get_or_insert((offset, id.len()));
+    }
+    fallback
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_component() {
+        let desc = "Meter:2 can't have successors with categories [Battery]. Found Battery:12.";
+        assert_eq!(find_component(desc, 2), Some((0, 7)));
+        assert_eq!(find_component(desc, 12), Some((63, 10)));
+        assert_eq!(find_component(desc, 1), None);
+
+        let desc = "Nodes [11, 12] are not connected to the root.";
+        assert_eq!(find_component(desc, 12), Some((11, 2)));
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let err = Error::invalid_graph("Meter:2 can't have any successors. Found Battery:3.")
+            .with_components([2, 3])
+            .with_rule("leaf");
+
+        assert_eq!(
+            err.code().map(|c| c.to_string()),
+            Some("InvalidGraph::leaf".to_string())
+        );
+        assert_eq!(err.severity(), Some(Severity::Error));
+        assert_eq!(
+            err.labels().map(|l| l.collect::<Vec<_>>()),
+            Some(vec![
+                LabeledSpan::new_with_span(Some("component 2".to_string()), (0, 7)),
+                LabeledSpan::new_with_span(Some("related component 3".to_string()), (41, 9)),
+            ])
+        );
+        assert!(err.related().is_none());
+
+        let err = Error::lint("Meter:4 has no successors and is not a grid meter.");
+        assert_eq!(err.severity(), Some(Severity::Warning));
+        assert!(err.labels().is_none());
+
+        let err = Error::multiple(vec![
+            Error::invalid_graph("first"),
+            Error::invalid_graph("second"),
+        ]);
+        assert_eq!(err.related().map(|r| r.count()), Some(2));
+    }
+}
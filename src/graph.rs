@@ -4,14 +4,42 @@
 //! A graph representation of the electrical components that are part of a
 //! microgrid, and the connections between them.
 
+mod adjacency;
+mod ancestry;
+#[cfg(feature = "binary")]
+mod binary;
+mod canonical;
+mod capacity;
+mod coupling;
 mod creation;
+#[cfg(feature = "csv")]
+mod csv;
+mod display;
+mod feeders;
+mod html;
+#[cfg(feature = "serde")]
+mod json;
+mod lint;
 mod meter_roles;
+mod plantuml;
+mod quarantine;
+mod reachability;
 mod retrieval;
+mod summary;
 mod validation;
+mod voltage_zones;
 
+pub mod invariants;
 pub mod iterators;
 
-use crate::{Edge, Node};
+pub use feeders::{Feeder, FeederRole};
+pub use meter_roles::MeterRole;
+pub use quarantine::Quarantine;
+pub use reachability::Reachability;
+pub use summary::GraphSummary;
+pub use validation::{validate_components, ValidationReport};
+
+use crate::{ComponentGraphConfig, Edge, Node, SiteMetadata};
 use petgraph::graph::{DiGraph, NodeIndex};
 use std::collections::HashMap;
 
@@ -28,6 +56,36 @@ pub(crate) type NodeIndexMap = HashMap<u64, NodeIndex>;
 /// destination `NodeIndex` values.
 pub(crate) type EdgeMap<E> = HashMap<(NodeIndex, NodeIndex), E>;
 
+/// The direct neighbors of a node in the `DiGraph`, sorted by their
+/// `component_id`s.
+///
+/// These are computed once when the graph is created, so that neighbor
+/// lookups don't have to walk petgraph's edge lists every time.
+#[derive(Clone, Default)]
+pub(crate) struct Adjacency {
+    pub(crate) predecessors: Vec<NodeIndex>,
+    pub(crate) successors: Vec<NodeIndex>,
+}
+
+/// Records the time since its creation in the `elapsed_us` field of the
+/// current span, when dropped.
+#[cfg(feature = "tracing")]
+pub(crate) struct ElapsedRecorder(std::time::Instant);
+
+#[cfg(feature = "tracing")]
+impl ElapsedRecorder {
+    pub(crate) fn start() -> Self {
+        Self(std::time::Instant::now())
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for ElapsedRecorder {
+    fn drop(&mut self) {
+        tracing::Span::current().record("elapsed_us", self.0.elapsed().as_micros() as u64);
+    }
+}
+
 /// A graph representation of the electrical components of a microgrid and the
 /// connections between them.
 pub struct ComponentGraph<N, E>
@@ -37,6 +95,15 @@ where
 {
     graph: DiGraph<N, ()>,
     node_indices: NodeIndexMap,
+    /// The `NodeIndex` of each component that has an external ID.
+    external_indices: HashMap<String, NodeIndex>,
     root_id: u64,
     edges: EdgeMap<E>,
+    /// The neighbors of each node, addressed by the index of its `NodeIndex`.
+    adjacency: Vec<Adjacency>,
+    config: ComponentGraphConfig,
+    metadata: SiteMetadata,
+    /// The roles of all meters in the graph, computed the first time a meter
+    /// role is requested.
+    meter_roles: meter_roles::MeterRoleCache,
 }
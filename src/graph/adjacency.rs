@@ -0,0 +1,106 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for exporting the topology of a [`ComponentGraph`] as an
+//! adjacency list or matrix.
+
+use std::collections::BTreeMap;
+
+use crate::{ComponentGraph, Edge, Node};
+
+/// Adjacency export.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns the successors of each component, by component ID.
+    ///
+    /// Every component has an entry, with an empty list for components
+    /// without successors, and the successors are in the order of their IDs.
+    pub fn to_adjacency_list(&self) -> BTreeMap<u64, Vec<u64>> {
+        self.components()
+            .map(|c| {
+                let index = self.node_indices[&c.component_id()];
+                let successors = self.adjacency[index.index()]
+                    .successors
+                    .iter()
+                    .map(|&s| self.graph[s].component_id())
+                    .collect();
+                (c.component_id(), successors)
+            })
+            .collect()
+    }
+
+    /// Returns the component IDs in ascending order, and a dense adjacency
+    /// matrix with rows and columns in that order.
+    ///
+    /// The entry at `[i][j]` is `1` if there is a connection from the `i`-th
+    /// component to the `j`-th component, and `0` otherwise.
+    pub fn to_adjacency_matrix(&self) -> (Vec<u64>, Vec<Vec<u8>>) {
+        let list = self.to_adjacency_list();
+        let positions = list
+            .keys()
+            .enumerate()
+            .map(|(position, &cid)| (cid, position))
+            .collect::<BTreeMap<_, _>>();
+        let matrix = list
+            .values()
+            .map(|successors| {
+                let mut row = vec![0; list.len()];
+                for successor in successors {
+                    row[positions[successor]] = 1;
+                }
+                row
+            })
+            .collect();
+        (list.into_keys().collect(), matrix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builder::ComponentGraphBuilder, Error};
+
+    #[test]
+    fn test_adjacency() -> Result<(), Error> {
+        let mut builder = ComponentGraphBuilder::new();
+        let grid = builder.grid();
+        let grid_meter = builder.meter();
+        let bat_chain = builder.meter_bat_chain(2, 1);
+        builder
+            .connect(grid, grid_meter)
+            .connect(grid_meter, bat_chain);
+        let graph = builder.build()?;
+        // Grid:1 -> Meter:2 -> Meter:3 -> BatteryInverter:4,5 -> Battery:6
+
+        assert_eq!(
+            graph.to_adjacency_list(),
+            BTreeMap::from([
+                (1, vec![2]),
+                (2, vec![3]),
+                (3, vec![4, 5]),
+                (4, vec![6]),
+                (5, vec![6]),
+                (6, Vec::<u64>::new()),
+            ])
+        );
+
+        let (ids, matrix) = graph.to_adjacency_matrix();
+        assert_eq!(ids, [1, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            matrix,
+            [
+                [0, 1, 0, 0, 0, 0],
+                [0, 0, 1, 0, 0, 0],
+                [0, 0, 0, 1, 1, 0],
+                [0, 0, 0, 0, 0, 1],
+                [0, 0, 0, 0, 0, 1],
+                [0, 0, 0, 0, 0, 0],
+            ]
+        );
+
+        Ok(())
+    }
+}
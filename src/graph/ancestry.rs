@@ -0,0 +1,199 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for finding the components above a component of a
+//! [`ComponentGraph`], on the way up to the grid.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::{component_category::CategoryPredicates, ComponentGraph, Edge, Error, Node};
+
+/// Ancestry queries.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns the meters on the path from the component with the given
+    /// `component_id` up to the grid, starting with the nearest one.
+    ///
+    /// The component itself is not included, even if it is a meter.  When a
+    /// component has multiple predecessors, the path continues through the
+    /// one with the lowest component ID.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn metering_chain(&self, component_id: u64) -> Result<Vec<&N>, Error> {
+        let mut meters = vec![];
+        let mut current = self.component(component_id)?;
+        while let Some(predecessor) = self.predecessors(current.component_id())?.next() {
+            if predecessor.is_meter() {
+                meters.push(predecessor);
+            }
+            current = predecessor;
+        }
+        Ok(meters)
+    }
+
+    /// Returns the number of connections on the shortest path from the grid
+    /// to the component with the given `component_id`.
+    ///
+    /// The distance of the grid is `0`.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn grid_distance(&self, component_id: u64) -> Result<usize, Error> {
+        self.component(component_id)?;
+        let mut visited = HashSet::from([component_id]);
+        let mut queue = VecDeque::from([(component_id, 0)]);
+        while let Some((id, distance)) = queue.pop_front() {
+            if id == self.root_id {
+                return Ok(distance);
+            }
+            for predecessor in self.predecessors(id)? {
+                if visited.insert(predecessor.component_id()) {
+                    queue.push_back((predecessor.component_id(), distance + 1));
+                }
+            }
+        }
+        Err(Error::internal(format!(
+            "Component {component_id} is not reachable from the grid."
+        )))
+    }
+
+    /// Returns the lowest meter that is above or equal to both the components
+    /// with the IDs `a` and `b`, if there is one.
+    ///
+    /// This is the meter whose readings include both components, and is
+    /// closest to them.  Among common meters, the one farthest from the grid
+    /// is returned, and for equal distances, the one with the lowest
+    /// component ID.
+    ///
+    /// Returns an error if either of the given component IDs doesn't exist.
+    pub fn first_common_meter(&self, a: u64, b: u64) -> Result<Option<&N>, Error> {
+        let above_a = self.ancestors_and_self(a)?;
+        let mut best: Option<(&N, usize)> = None;
+        for id in self.ancestors_and_self(b)? {
+            let component = self.component(id)?;
+            if !component.is_meter() || !above_a.contains(&id) {
+                continue;
+            }
+            let distance = self.grid_distance(id)?;
+            let is_better = best.is_none_or(|(current, current_distance)| {
+                (distance, std::cmp::Reverse(id))
+                    > (current_distance, std::cmp::Reverse(current.component_id()))
+            });
+            if is_better {
+                best = Some((component, distance));
+            }
+        }
+        Ok(best.map(|(meter, _)| meter))
+    }
+
+    /// Returns the IDs of the component with the given `component_id` and of
+    /// all the components above it.
+    fn ancestors_and_self(&self, component_id: u64) -> Result<HashSet<u64>, Error> {
+        self.component(component_id)?;
+        let mut ancestors = HashSet::from([component_id]);
+        let mut stack = vec![component_id];
+        while let Some(id) = stack.pop() {
+            for predecessor in self.predecessors(id)? {
+                if ancestors.insert(predecessor.component_id()) {
+                    stack.push(predecessor.component_id());
+                }
+            }
+        }
+        Ok(ancestors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::ComponentGraphBuilder;
+
+    #[test]
+    fn test_metering_chain() -> Result<(), Error> {
+        let mut builder = ComponentGraphBuilder::new();
+        let grid = builder.grid();
+        let grid_meter = builder.meter();
+        let sub_meter = builder.meter();
+        let bat_chain = builder.meter_bat_chain(1, 1);
+        let pv_inverter = builder.solar_inverter();
+        builder
+            .connect(grid, grid_meter)
+            .connect(grid_meter, sub_meter)
+            .connect(sub_meter, bat_chain)
+            .connect(grid_meter, pv_inverter);
+        let graph = builder.build()?;
+        // Grid:1 -> Meter:2 -> Meter:3 -> Meter:4 -> BatteryInverter:5 -> Battery:6
+        //                   -> SolarInverter:7
+        let chain = |id| -> Result<Vec<u64>, Error> {
+            Ok(graph
+                .metering_chain(id)?
+                .into_iter()
+                .map(|n| n.component_id())
+                .collect())
+        };
+
+        assert_eq!(chain(6)?, vec![4, 3, 2]);
+        assert_eq!(chain(4)?, vec![3, 2]);
+        assert_eq!(chain(7)?, vec![2]);
+        assert!(chain(2)?.is_empty());
+        assert!(chain(1)?.is_empty());
+        assert_eq!(
+            graph.metering_chain(8),
+            Err(Error::component_not_found("Component with id 8 not found."))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_grid_distance_and_first_common_meter() -> Result<(), Error> {
+        let mut builder = ComponentGraphBuilder::new();
+        let grid = builder.grid();
+        let grid_meter = builder.meter();
+        let sub_meter = builder.meter();
+        let bat_chain = builder.meter_bat_chain(2, 1);
+        let pv_chain = builder.meter_pv_chain(1);
+        let chp_chain = builder.meter_chp_chain(1);
+        builder
+            .connect(grid, grid_meter)
+            .connect(grid_meter, sub_meter)
+            .connect(sub_meter, bat_chain)
+            .connect(sub_meter, pv_chain)
+            .connect(grid_meter, chp_chain);
+        let graph = builder.build()?;
+        // Grid:1 -> Meter:2 -> Meter:3 -> Meter:4 -> BatteryInverter:5,6 -> Battery:7
+        //                              -> Meter:8 -> SolarInverter:9
+        //                   -> Meter:10 -> CHP:11
+
+        assert_eq!(graph.grid_distance(1)?, 0);
+        assert_eq!(graph.grid_distance(2)?, 1);
+        assert_eq!(graph.grid_distance(7)?, 5);
+        assert_eq!(graph.grid_distance(9)?, 4);
+
+        let common = |a, b| -> Result<Option<u64>, Error> {
+            Ok(graph.first_common_meter(a, b)?.map(|n| n.component_id()))
+        };
+        assert_eq!(common(5, 6)?, Some(4));
+        assert_eq!(common(7, 9)?, Some(3));
+        assert_eq!(common(9, 11)?, Some(2));
+        assert_eq!(common(11, 9)?, Some(2));
+        assert_eq!(common(4, 7)?, Some(4));
+        assert_eq!(common(1, 7)?, None);
+
+        for result in [
+            graph.grid_distance(12).map(|_| ()),
+            common(1, 12).map(|_| ()),
+        ] {
+            assert_eq!(
+                result,
+                Err(Error::component_not_found(
+                    "Component with id 12 not found."
+                ))
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for saving a validated [`ComponentGraph`] in a compact binary
+//! format, and restoring it without validating it again.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{ComponentGraph, ComponentGraphConfig, Edge, Error, MeterRole, Node, SiteMetadata};
+
+/// The version of the binary format, which is increased on incompatible
+/// changes.
+const FORMAT_VERSION: u32 = 1;
+
+/// The binary representation of a graph, borrowing from the graph when
+/// saving it.
+#[derive(Serialize)]
+struct SavedGraph<'a, N, E> {
+    version: u32,
+    config: &'a ComponentGraphConfig,
+    metadata: &'a SiteMetadata,
+    components: Vec<&'a N>,
+    connections: Vec<&'a E>,
+    meter_roles: Vec<(u64, MeterRole)>,
+}
+
+/// The binary representation of a graph, owning its data when restoring it.
+#[derive(Deserialize)]
+struct RestoredGraph<N, E> {
+    version: u32,
+    config: ComponentGraphConfig,
+    metadata: SiteMetadata,
+    components: Vec<N>,
+    connections: Vec<E>,
+    meter_roles: Vec<(u64, MeterRole)>,
+}
+
+/// Binary snapshots.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns a binary representation of the graph, with its config, site
+    /// metadata and the roles of its meters.
+    ///
+    /// The representation is encoded with MessagePack, and can be restored
+    /// with [`from_bytes`][Self::from_bytes].
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error>
+    where
+        N: Serialize,
+        E: Serialize,
+    {
+        let mut meter_roles = self
+            .meter_roles()
+            .iter()
+            .map(|(&cid, &role)| (cid, role))
+            .collect::<Vec<_>>();
+        meter_roles.sort_by_key(|(cid, _)| *cid);
+
+        rmp_serde::to_vec_named(&SavedGraph {
+            version: FORMAT_VERSION,
+            config: &self.config,
+            metadata: &self.metadata,
+            components: self.components().collect(),
+            connections: self.connections().collect(),
+            meter_roles,
+        })
+        .map_err(|e| Error::serialization(format!("Unable to serialize graph: {e}")))
+    }
+
+    /// Restores a graph from the binary representation returned by
+    /// [`to_bytes`][Self::to_bytes].
+    ///
+    /// The graph was validated when it was saved, so the rules for the
+    /// neighbors of each component category are not checked again, and the
+    /// roles of its meters are not recomputed.  The checks needed to build the
+    /// graph are run, like those for duplicate components, and so are the
+    /// checks that the graph is acyclic and connected, which the other
+    /// methods of the graph rely on.  This way, truncated or tampered bytes
+    /// can't produce a graph that other methods can't handle.  Still, only
+    /// restore bytes from trusted sources, as the restored meter roles and
+    /// category rules are taken as they are.
+    ///
+    /// Returns an error if the bytes can't be decoded, were written by an
+    /// incompatible version of this library, or describe a graph that has a
+    /// cycle or components that are not connected to the grid.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error>
+    where
+        N: DeserializeOwned,
+        E: DeserializeOwned,
+    {
+        let data: RestoredGraph<N, E> = rmp_serde::from_slice(bytes)
+            .map_err(|e| Error::serialization(format!("Unable to parse graph: {e}")))?;
+        if data.version != FORMAT_VERSION {
+            return Err(Error::serialization(format!(
+                "Unsupported binary format version: {}, expected {FORMAT_VERSION}.",
+                data.version
+            )));
+        }
+
+        let cg = Self::try_build(data.components, data.connections, data.config)?
+            .with_metadata(data.metadata);
+        cg.validate_structure()?;
+        cg.meter_roles.set(data.meter_roles.into_iter().collect());
+        Ok(cg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentCategory, ErrorKind, InverterType, SimpleComponent, SimpleConnection};
+
+    #[test]
+    fn test_binary_roundtrip() -> Result<(), Error> {
+        let graph = ComponentGraph::try_new(
+            vec![
+                SimpleComponent::new(1, ComponentCategory::Grid),
+                SimpleComponent::new(2, ComponentCategory::Meter).with_name("Main meter"),
+                SimpleComponent::new(3, ComponentCategory::Meter),
+                SimpleComponent::new(4, ComponentCategory::Inverter(InverterType::Solar)),
+            ],
+            vec![
+                SimpleConnection::new(1, 2),
+                SimpleConnection::new(2, 3),
+                SimpleConnection::new(3, 4),
+            ],
+        )?
+        .with_metadata(SiteMetadata {
+            microgrid_id: Some(12),
+            ..Default::default()
+        });
+
+        let bytes = graph.to_bytes()?;
+        let restored = ComponentGraph::<SimpleComponent, SimpleConnection>::from_bytes(&bytes)?;
+
+        assert!(restored.components().eq(graph.components()));
+        assert!(restored.connections().eq(graph.connections()));
+        assert_eq!(restored.config(), graph.config());
+        assert_eq!(restored.metadata(), graph.metadata());
+        assert!(restored.meter_roles.get().is_some());
+        assert_eq!(restored.meter_role(3)?, Some(MeterRole::Pv));
+        assert_eq!(restored.to_string(), graph.to_string());
+
+        let Err(err) = ComponentGraph::<SimpleComponent, SimpleConnection>::from_bytes(&bytes[1..])
+        else {
+            panic!("expected an error");
+        };
+        assert_eq!(err.kind(), ErrorKind::Serialization);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_structure_checks() -> Result<(), Error> {
+        let bytes = |components: &[SimpleComponent], connections: &[SimpleConnection]| {
+            rmp_serde::to_vec_named(&SavedGraph {
+                version: FORMAT_VERSION,
+                config: &ComponentGraphConfig::default(),
+                metadata: &SiteMetadata::default(),
+                components: components.iter().collect(),
+                connections: connections.iter().collect(),
+                meter_roles: vec![],
+            })
+            .map_err(|e| Error::serialization(e.to_string()))
+        };
+        let mut components = vec![
+            SimpleComponent::new(1, ComponentCategory::Grid),
+            SimpleComponent::new(2, ComponentCategory::Meter),
+            SimpleComponent::new(3, ComponentCategory::Meter),
+            SimpleComponent::new(4, ComponentCategory::Meter),
+        ];
+        let mut connections = vec![
+            SimpleConnection::new(1, 2),
+            SimpleConnection::new(2, 3),
+            SimpleConnection::new(3, 4),
+        ];
+        assert!(
+            ComponentGraph::<SimpleComponent, SimpleConnection>::from_bytes(&bytes(
+                &components,
+                &connections
+            )?)
+            .is_ok()
+        );
+
+        // The neighbor rules of the categories are not checked.
+        components[3].category = ComponentCategory::Battery;
+        assert!(
+            ComponentGraph::<SimpleComponent, SimpleConnection>::from_bytes(&bytes(
+                &components,
+                &connections
+            )?)
+            .is_ok()
+        );
+
+        // A cycle is rejected.
+        components[3].category = ComponentCategory::Meter;
+        connections.push(SimpleConnection::new(4, 2));
+        assert_eq!(
+            ComponentGraph::<SimpleComponent, SimpleConnection>::from_bytes(&bytes(
+                &components,
+                &connections
+            )?)
+            .err(),
+            Some(Error::invalid_graph("Cycle detected: 2 -> 3 -> 4 -> 2"))
+        );
+
+        // And so are components that are not connected to the grid.
+        connections.pop();
+        components.push(SimpleComponent::new(5, ComponentCategory::Meter));
+        assert_eq!(
+            ComponentGraph::<SimpleComponent, SimpleConnection>::from_bytes(&bytes(
+                &components,
+                &connections
+            )?)
+            .map_err(|e| e.kind())
+            .err(),
+            Some(ErrorKind::InvalidGraph)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for renumbering the components of a [`ComponentGraph`] into a
+//! normal form.
+
+use std::collections::{BTreeMap, HashMap};
+
+use petgraph::{algo::toposort, graph::NodeIndex};
+
+use crate::{ComponentGraph, Edge, Error, Node, SimpleComponent, SimpleConnection};
+
+/// A graph in the normal form returned by `canonicalize`.
+type CanonicalGraph = ComponentGraph<SimpleComponent, SimpleConnection>;
+
+/// Canonical relabeling.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns a copy of the graph with the components renumbered into a
+    /// normal form, together with a map from the original component IDs to
+    /// the new ones.
+    ///
+    /// The new IDs start from `1` at the grid, and are assigned depth-first,
+    /// visiting the successors of each component in the order of the shape
+    /// of the graph below them, with the shallowest shapes first.  They don't depend on the original IDs, so
+    /// two sites with the same topology get identical canonical graphs.
+    ///
+    /// The canonical graph is made of [`SimpleComponent`]s and
+    /// [`SimpleConnection`]s, and is validated with the same config as this
+    /// one.
+    pub fn canonicalize(&self) -> Result<(CanonicalGraph, BTreeMap<u64, u64>), Error> {
+        let shapes = self.shapes()?;
+
+        let mut mapping = BTreeMap::new();
+        let mut stack = vec![self.node_indices[&self.root_id]];
+        while let Some(index) = stack.pop() {
+            let cid = self.graph[index].component_id();
+            if mapping.contains_key(&cid) {
+                continue;
+            }
+            mapping.insert(cid, mapping.len() as u64 + 1);
+
+            let mut successors = self.adjacency[index.index()].successors.clone();
+            successors.sort_by(|a, b| shapes[a].cmp(&shapes[b]));
+            // Pushed in reverse, so that the first successor is visited first.
+            stack.extend(successors.into_iter().rev());
+        }
+
+        let mut components = self
+            .components()
+            .map(|c| SimpleComponent::new(mapping[&c.component_id()], c.category()))
+            .collect::<Vec<_>>();
+        components.sort_by_key(|c| c.id);
+        let mut connections = self
+            .connections()
+            .map(|c| SimpleConnection::new(mapping[&c.source()], mapping[&c.destination()]))
+            .collect::<Vec<_>>();
+        connections.sort_by_key(|c| (c.source, c.destination));
+
+        let canonical =
+            ComponentGraph::try_new_with_config(components, connections, self.config.clone())?;
+        Ok((canonical, mapping))
+    }
+
+    /// Returns a class for the shape of the graph below each component,
+    /// made of the categories of the components, that doesn't depend on
+    /// their IDs.
+    ///
+    /// Components have the same class if the graphs below them have the same
+    /// shape.  Classes are assigned bottom-up, level by level from the
+    /// leaves, from the category of each component and the sorted classes of
+    /// its successors, so that their order is the same for every numbering of
+    /// the components.
+    fn shapes(&self) -> Result<HashMap<NodeIndex, u32>, Error> {
+        let order = toposort(&self.graph, None)
+            .map_err(|_| Error::internal("Unable to sort the components of a cyclic graph."))?;
+
+        // The length of the longest path from each component to a leaf.
+        let mut heights: HashMap<NodeIndex, usize> = HashMap::new();
+        let mut levels: BTreeMap<usize, Vec<NodeIndex>> = BTreeMap::new();
+        for index in order.into_iter().rev() {
+            let height = self.adjacency[index.index()]
+                .successors
+                .iter()
+                .map(|s| heights[s] + 1)
+                .max()
+                .unwrap_or(0);
+            heights.insert(index, height);
+            levels.entry(height).or_default().push(index);
+        }
+
+        let mut shapes: HashMap<NodeIndex, u32> = HashMap::new();
+        let mut next_class = 0;
+        for indices in levels.into_values() {
+            let keys = indices
+                .into_iter()
+                .map(|index| {
+                    let mut successors = self.adjacency[index.index()]
+                        .successors
+                        .iter()
+                        .map(|s| shapes[s])
+                        .collect::<Vec<_>>();
+                    successors.sort();
+                    (
+                        index,
+                        (self.graph[index].category().to_string(), successors),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let mut classes = keys
+                .iter()
+                .map(|(_, key)| (key, 0))
+                .collect::<BTreeMap<_, u32>>();
+            for class in classes.values_mut() {
+                *class = next_class;
+                next_class += 1;
+            }
+            for (index, key) in &keys {
+                shapes.insert(*index, classes[key]);
+            }
+        }
+        Ok(shapes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentCategory, InverterType};
+
+    fn graph(ids: [u64; 7]) -> Result<ComponentGraph<SimpleComponent, SimpleConnection>, Error> {
+        let [grid, meter, pv_meter, pv_inverter, bat_meter, bat_inverter, battery] = ids;
+        ComponentGraph::try_new(
+            vec![
+                SimpleComponent::new(grid, ComponentCategory::Grid),
+                SimpleComponent::new(meter, ComponentCategory::Meter),
+                SimpleComponent::new(pv_meter, ComponentCategory::Meter),
+                SimpleComponent::new(
+                    pv_inverter,
+                    ComponentCategory::Inverter(InverterType::Solar),
+                ),
+                SimpleComponent::new(bat_meter, ComponentCategory::Meter),
+                SimpleComponent::new(
+                    bat_inverter,
+                    ComponentCategory::Inverter(InverterType::Battery),
+                ),
+                SimpleComponent::new(battery, ComponentCategory::Battery),
+            ],
+            vec![
+                SimpleConnection::new(grid, meter),
+                SimpleConnection::new(meter, pv_meter),
+                SimpleConnection::new(pv_meter, pv_inverter),
+                SimpleConnection::new(meter, bat_meter),
+                SimpleConnection::new(bat_meter, bat_inverter),
+                SimpleConnection::new(bat_inverter, battery),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_canonicalize() -> Result<(), Error> {
+        let first = graph([1, 2, 3, 4, 5, 6, 7])?;
+        let second = graph([100, 42, 7, 8, 3, 20, 21])?;
+
+        let (first_canonical, first_mapping) = first.canonicalize()?;
+        let (second_canonical, second_mapping) = second.canonicalize()?;
+
+        assert!(first_canonical
+            .components()
+            .eq(second_canonical.components()));
+        assert!(first_canonical
+            .connections()
+            .eq(second_canonical.connections()));
+        assert_eq!(
+            first_canonical.to_string(),
+            "\
+Grid:1
+└── Meter:2 (Grid meter)
+    ├── Meter:3 (PV meter)
+    │   └── SolarInverter:4
+    └── Meter:5 (Battery meter)
+        └── BatteryInverter:6
+            └── Battery:7
+"
+        );
+
+        assert_eq!(
+            first_mapping,
+            BTreeMap::from([(1, 1), (2, 2), (3, 3), (4, 4), (5, 5), (6, 6), (7, 7)])
+        );
+        assert_eq!(
+            second_mapping,
+            BTreeMap::from([(100, 1), (42, 2), (7, 3), (8, 4), (3, 5), (20, 6), (21, 7)])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonicalize_stacked_diamonds() -> Result<(), Error> {
+        // Each diamond doubles the number of paths from the grid, which must
+        // not make the shapes grow exponentially.
+        let diamonds = |offset: u64| {
+            let mut components = vec![
+                SimpleComponent::new(offset + 1, ComponentCategory::Grid),
+                SimpleComponent::new(offset + 2, ComponentCategory::Meter),
+            ];
+            let mut connections = vec![SimpleConnection::new(offset + 1, offset + 2)];
+            let mut top = offset + 2;
+            for _ in 0..40 {
+                let (left, right, bottom) = (top + 1, top + 2, top + 3);
+                for id in [left, right, bottom] {
+                    components.push(SimpleComponent::new(id, ComponentCategory::Meter));
+                }
+                connections.extend([
+                    SimpleConnection::new(top, left),
+                    SimpleConnection::new(top, right),
+                    SimpleConnection::new(left, bottom),
+                    SimpleConnection::new(right, bottom),
+                ]);
+                top = bottom;
+            }
+            // Reversed, so that the two graphs are built in different orders.
+            if offset > 0 {
+                components.reverse();
+                connections.reverse();
+            }
+            ComponentGraph::try_new(components, connections)
+        };
+
+        let (first, _) = diamonds(0)?.canonicalize()?;
+        let (second, mapping) = diamonds(1000)?.canonicalize()?;
+
+        assert_eq!(first.components().count(), 122);
+        assert!(first.components().eq(second.components()));
+        assert!(first.connections().eq(second.connections()));
+        assert_eq!(mapping[&1001], 1);
+        assert_eq!(mapping.len(), 122);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for adding up the rated powers of the components in a
+//! [`ComponentGraph`].
+
+use std::collections::HashSet;
+
+use crate::{ComponentCategory, ComponentGraph, Edge, Error, Node};
+
+/// Capacity aggregation.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns the combined rated power, in watts, of all components of the
+    /// given `category`.
+    ///
+    /// Components without a known rated power are not counted.
+    pub fn aggregate_capacity(&self, category: ComponentCategory) -> f64 {
+        self.components()
+            .filter(|n| n.category() == category)
+            .filter_map(|n| n.rated_power())
+            .sum()
+    }
+
+    /// Returns the combined rated power, in watts, of the components of the
+    /// given `category` that are downstream of the component with the given
+    /// `component_id`.
+    ///
+    /// Components reachable through multiple paths are only counted once,
+    /// and components without a known rated power are not counted.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn aggregate_capacity_downstream(
+        &self,
+        component_id: u64,
+        category: ComponentCategory,
+    ) -> Result<f64, Error> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![component_id];
+        let mut capacity = 0.0;
+        while let Some(id) = stack.pop() {
+            for successor in self.successors(id)? {
+                if !visited.insert(successor.component_id()) {
+                    continue;
+                }
+                if successor.category() == category {
+                    capacity += successor.rated_power().unwrap_or(0.0);
+                }
+                stack.push(successor.component_id());
+            }
+        }
+        Ok(capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InverterType;
+
+    #[derive(Clone)]
+    struct TestComponent(u64, ComponentCategory, Option<f64>);
+
+    impl Node for TestComponent {
+        fn component_id(&self) -> u64 {
+            self.0
+        }
+
+        fn category(&self) -> ComponentCategory {
+            self.1
+        }
+
+        fn is_supported(&self) -> bool {
+            true
+        }
+
+        fn rated_power(&self) -> Option<f64> {
+            self.2
+        }
+    }
+
+    #[derive(Clone)]
+    struct TestConnection(u64, u64);
+
+    impl Edge for TestConnection {
+        fn source(&self) -> u64 {
+            self.0
+        }
+
+        fn destination(&self) -> u64 {
+            self.1
+        }
+    }
+
+    #[test]
+    fn test_aggregate_capacity() -> Result<(), Error> {
+        let solar = ComponentCategory::Inverter(InverterType::Solar);
+        let battery = ComponentCategory::Inverter(InverterType::Battery);
+        let graph = ComponentGraph::try_new(
+            vec![
+                TestComponent(1, ComponentCategory::Grid, None),
+                TestComponent(2, ComponentCategory::Meter, None),
+                TestComponent(3, ComponentCategory::Meter, None),
+                TestComponent(4, solar, Some(10_000.0)),
+                TestComponent(5, solar, Some(5_000.0)),
+                TestComponent(6, solar, None),
+                TestComponent(7, ComponentCategory::Meter, None),
+                TestComponent(8, battery, Some(20_000.0)),
+                TestComponent(9, battery, Some(20_000.0)),
+                TestComponent(10, ComponentCategory::Battery, Some(30_000.0)),
+                TestComponent(11, ComponentCategory::Meter, None),
+                TestComponent(12, solar, Some(8_000.0)),
+            ],
+            vec![
+                TestConnection(1, 2),
+                TestConnection(2, 3),
+                TestConnection(3, 4),
+                TestConnection(3, 5),
+                TestConnection(3, 6),
+                TestConnection(2, 7),
+                TestConnection(7, 8),
+                TestConnection(7, 9),
+                TestConnection(8, 10),
+                TestConnection(9, 10),
+                TestConnection(1, 11),
+                TestConnection(11, 12),
+            ],
+        )?;
+
+        assert_eq!(graph.aggregate_capacity(solar), 23_000.0);
+        assert_eq!(graph.aggregate_capacity(battery), 40_000.0);
+        assert_eq!(
+            graph.aggregate_capacity(ComponentCategory::Battery),
+            30_000.0
+        );
+        assert_eq!(graph.aggregate_capacity(ComponentCategory::Chp), 0.0);
+
+        assert_eq!(graph.aggregate_capacity_downstream(2, solar)?, 15_000.0);
+        assert_eq!(graph.aggregate_capacity_downstream(1, solar)?, 23_000.0);
+        // The battery is shared by two inverters, but only counted once.
+        assert_eq!(
+            graph.aggregate_capacity_downstream(2, ComponentCategory::Battery)?,
+            30_000.0
+        );
+        assert_eq!(graph.aggregate_capacity_downstream(4, solar)?, 0.0);
+        assert_eq!(
+            graph.aggregate_capacity_downstream(13, solar),
+            Err(Error::component_not_found(
+                "Component with id 13 not found."
+            ))
+        );
+
+        Ok(())
+    }
+}
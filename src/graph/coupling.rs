@@ -0,0 +1,96 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for finding the batteries of a [`ComponentGraph`] that are coupled
+//! through shared inverters.
+
+use std::collections::BTreeSet;
+
+use crate::{component_category::CategoryPredicates, ComponentGraph, Edge, Error, Node};
+
+/// Battery coupling.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns the IDs of the batteries that are coupled with the battery
+    /// with the given `battery_id`, including the battery itself.
+    ///
+    /// Batteries are coupled when they are connected to the same inverter,
+    /// directly or through other coupled batteries.  Coupled batteries can
+    /// only be controlled together, so sets of battery IDs should contain
+    /// either all or none of the batteries in each coupled set.
+    ///
+    /// Returns an error if the given `battery_id` does not exist, or is not a
+    /// battery.
+    pub fn coupled_batteries(&self, battery_id: u64) -> Result<BTreeSet<u64>, Error> {
+        if !self.component(battery_id)?.is_battery() {
+            return Err(Error::invalid_component(format!(
+                "Component {battery_id} is not a battery."
+            ))
+            .with_components([battery_id]));
+        }
+
+        let mut batteries = BTreeSet::from([battery_id]);
+        let mut stack = vec![battery_id];
+        while let Some(id) = stack.pop() {
+            for inverter in self.predecessors(id)?.filter(|n| n.is_inverter()) {
+                for battery in self.successors(inverter.component_id())? {
+                    if battery.is_battery() && batteries.insert(battery.component_id()) {
+                        stack.push(battery.component_id());
+                    }
+                }
+            }
+        }
+        Ok(batteries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builder::ComponentGraphBuilder, ComponentCategory, InverterType};
+
+    #[test]
+    fn test_coupled_batteries() -> Result<(), Error> {
+        let mut builder = ComponentGraphBuilder::new();
+        let grid = builder.grid();
+        let grid_meter = builder.meter();
+        let shared_chain = builder.meter_bat_chain(3, 2);
+        let single_chain = builder.meter_bat_chain(1, 1);
+        let hybrid_meter = builder.meter();
+        let hybrid = builder.add_component(ComponentCategory::Inverter(InverterType::Hybrid));
+        let hybrid_batteries = [builder.battery(), builder.battery()];
+        builder
+            .connect(grid, grid_meter)
+            .connect(grid_meter, shared_chain)
+            .connect(grid_meter, single_chain)
+            .connect(grid_meter, hybrid_meter)
+            .connect(hybrid_meter, hybrid)
+            .connect(hybrid, hybrid_batteries[0])
+            .connect(hybrid, hybrid_batteries[1]);
+        let graph = builder.build()?;
+        // Grid:1 -> Meter:2 -> Meter:3 -> BatteryInverter:4,5,6 -> Battery:7,8
+        //                   -> Meter:9 -> BatteryInverter:10 -> Battery:11
+        //                   -> Meter:12 -> HybridInverter:13 -> Battery:14,15
+
+        assert_eq!(graph.coupled_batteries(7)?, BTreeSet::from([7, 8]));
+        assert_eq!(graph.coupled_batteries(8)?, BTreeSet::from([7, 8]));
+        assert_eq!(graph.coupled_batteries(11)?, BTreeSet::from([11]));
+        assert_eq!(graph.coupled_batteries(15)?, BTreeSet::from([14, 15]));
+
+        assert_eq!(
+            graph.coupled_batteries(4),
+            Err(Error::invalid_component("Component 4 is not a battery."))
+        );
+        assert_eq!(
+            graph.coupled_batteries(16),
+            Err(Error::component_not_found(
+                "Component with id 16 not found."
+            ))
+        );
+
+        Ok(())
+    }
+}
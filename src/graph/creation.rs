@@ -4,11 +4,15 @@
 //! Methods for creating [`ComponentGraph`] instances from given components and
 //! connections.
 
-use petgraph::graph::DiGraph;
+use petgraph::graph::{DiGraph, NodeIndex};
+use std::collections::{HashMap, HashSet};
 
-use crate::{component_category::CategoryPredicates, Edge, Error, Node};
+use crate::{
+    component_category::CategoryPredicates, ComponentGraphConfig, Edge, Error, Node,
+    OtherCategoryPolicy, SiteMetadata,
+};
 
-use super::{ComponentGraph, EdgeMap, NodeIndexMap};
+use super::{Adjacency, ComponentGraph, EdgeMap, NodeIndexMap};
 
 /// `ComponentGraph` instantiation.
 impl<N, E> ComponentGraph<N, E>
@@ -23,22 +27,93 @@ where
         components: NodeIterator,
         connections: EdgeIterator,
     ) -> Result<Self, Error> {
-        let (graph, indices) = Self::create_graph(components)?;
+        Self::try_new_with_config(components, connections, ComponentGraphConfig::default())
+    }
+
+    /// Creates a new [`ComponentGraph`] from the given components and
+    /// connections, validating it according to the given `config`.
+    ///
+    /// Returns an error if the graph is invalid.
+    ///
+    /// With the `tracing` feature, this runs in a `ComponentGraph::try_new`
+    /// span, with the number of components and connections, and the time
+    /// it took.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "ComponentGraph::try_new",
+            level = "debug",
+            skip_all,
+            fields(
+                components = tracing::field::Empty,
+                connections = tracing::field::Empty,
+                elapsed_us = tracing::field::Empty,
+            ),
+            err
+        )
+    )]
+    pub fn try_new_with_config<
+        NodeIterator: IntoIterator<Item = N>,
+        EdgeIterator: IntoIterator<Item = E>,
+    >(
+        components: NodeIterator,
+        connections: EdgeIterator,
+        config: ComponentGraphConfig,
+    ) -> Result<Self, Error> {
+        #[cfg(feature = "tracing")]
+        let _elapsed = super::ElapsedRecorder::start();
+
+        let cg = Self::try_build(components, connections, config)?;
+
+        #[cfg(feature = "tracing")]
+        tracing::Span::current()
+            .record("components", cg.graph.node_count())
+            .record("connections", cg.graph.edge_count());
+
+        cg.validate()?;
+
+        Ok(cg)
+    }
+
+    /// Builds a [`ComponentGraph`] from the given components and connections,
+    /// without validating its structure.
+    ///
+    /// Returns an error only if the graph can't be built at all, for example
+    /// because of duplicate component IDs or connections to missing
+    /// components.
+    pub(crate) fn try_build(
+        components: impl IntoIterator<Item = N>,
+        connections: impl IntoIterator<Item = E>,
+        config: ComponentGraphConfig,
+    ) -> Result<Self, Error> {
+        let (graph, indices) = Self::create_graph(components, &config)?;
         let root_id = Self::find_root(&graph)?.component_id();
+        let external_indices = Self::index_external_ids(&graph)?;
 
         let mut cg = Self {
             graph,
             node_indices: indices,
+            external_indices,
             root_id,
             edges: EdgeMap::new(),
+            adjacency: vec![],
+            config,
+            metadata: SiteMetadata::default(),
+            meter_roles: Default::default(),
         };
         cg.add_connections(connections)?;
-
-        cg.validate()?;
+        cg.build_adjacency();
 
         Ok(cg)
     }
 
+    /// Attaches the given site `metadata` to the graph, replacing any
+    /// previously attached metadata.
+    pub fn with_metadata(mut self, metadata: SiteMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
     fn find_root(graph: &DiGraph<N, ()>) -> Result<&N, Error> {
         let mut roots_iter = graph.raw_nodes().iter().filter(|n| n.weight.is_grid());
 
@@ -48,7 +123,15 @@ where
             .ok_or_else(|| Error::invalid_graph("No grid component found."))?;
 
         if roots_iter.next().is_some() {
-            return Err(Error::invalid_graph("Multiple grid components found."));
+            return Err(
+                Error::invalid_graph("Multiple grid components found.").with_components(
+                    graph
+                        .raw_nodes()
+                        .iter()
+                        .filter(|n| n.weight.is_grid())
+                        .map(|n| n.weight.component_id()),
+                ),
+            );
         }
 
         Ok(root)
@@ -56,6 +139,7 @@ where
 
     fn create_graph(
         components: impl IntoIterator<Item = N>,
+        config: &ComponentGraphConfig,
     ) -> Result<(DiGraph<N, ()>, NodeIndexMap), Error> {
         let mut graph = DiGraph::new();
         let mut indices = NodeIndexMap::new();
@@ -66,17 +150,27 @@ where
             if component.is_unspecified() {
                 return Err(Error::invalid_component(format!(
                     "ComponentCategory not specified for component: {cid}"
-                )));
+                ))
+                .with_components([cid]));
             }
-            if component.is_unspecified_inverter() {
+            if component.is_other() && config.other_categories == OtherCategoryPolicy::Reject {
+                return Err(Error::invalid_component(format!(
+                    "Unknown ComponentCategory {} for component: {cid}",
+                    component.category()
+                ))
+                .with_components([cid]));
+            }
+            if component.is_unspecified_inverter() && !config.allow_unspecified_inverters {
                 return Err(Error::invalid_component(format!(
                     "InverterType not specified for inverter: {cid}"
-                )));
+                ))
+                .with_components([cid]));
             }
             if indices.contains_key(&cid) {
-                return Err(Error::invalid_graph(format!(
-                    "Duplicate component ID found: {cid}"
-                )));
+                return Err(
+                    Error::invalid_graph(format!("Duplicate component ID found: {cid}"))
+                        .with_components([cid]),
+                );
             }
 
             let idx = graph.add_node(component);
@@ -86,6 +180,44 @@ where
         Ok((graph, indices))
     }
 
+    fn index_external_ids(graph: &DiGraph<N, ()>) -> Result<HashMap<String, NodeIndex>, Error> {
+        let mut indices: HashMap<String, NodeIndex> = HashMap::new();
+        for index in graph.node_indices() {
+            let Some(external_id) = graph[index].external_id() else {
+                continue;
+            };
+            if let Some(&other) = indices.get(external_id) {
+                return Err(Error::invalid_graph(format!(
+                    "Duplicate external ID found: {external_id}"
+                ))
+                .with_components([graph[other].component_id(), graph[index].component_id()]));
+            }
+            indices.insert(external_id.to_string(), index);
+        }
+        Ok(indices)
+    }
+
+    fn build_adjacency(&mut self) {
+        let mut adjacency = vec![Adjacency::default(); self.graph.node_count()];
+        for edge in self.graph.raw_edges() {
+            adjacency[edge.source().index()]
+                .successors
+                .push(edge.target());
+            adjacency[edge.target().index()]
+                .predecessors
+                .push(edge.source());
+        }
+        for neighbors in adjacency.iter_mut() {
+            neighbors
+                .predecessors
+                .sort_by_key(|&i| self.graph[i].component_id());
+            neighbors
+                .successors
+                .sort_by_key(|&i| self.graph[i].component_id());
+        }
+        self.adjacency = adjacency;
+    }
+
     fn add_connections(&mut self, connections: impl IntoIterator<Item = E>) -> Result<(), Error> {
         for connection in connections {
             let sid = connection.source();
@@ -94,30 +226,95 @@ where
             if sid == did {
                 return Err(Error::invalid_connection(format!(
                     "Connection:({sid}, {did}) Can't connect a component to itself."
-                )));
+                ))
+                .with_connection(sid, did)
+                .with_components([sid]));
             }
             for cid in [sid, did] {
                 if !self.node_indices.contains_key(&cid) {
                     return Err(Error::invalid_connection(format!(
                         "Connection:({sid}, {did}) Can't find a component with ID {cid}"
-                    )));
+                    ))
+                    .with_connection(sid, did)
+                    .with_components([cid]));
                 }
             }
 
             let source_idx = self.node_indices[&connection.source()];
             let dest_idx = self.node_indices[&connection.destination()];
-            self.edges.insert((source_idx, dest_idx), connection);
-            self.graph.update_edge(source_idx, dest_idx, ());
+            if self.edges.contains_key(&(dest_idx, source_idx)) {
+                return Err(Error::invalid_connection(format!(
+                    "Connection:({sid}, {did}) Conflicts with the reversed connection ({did}, {sid})."
+                ))
+                .with_connection(sid, did)
+                .with_components([sid, did]));
+            }
+            // Duplicate connections replace the stored `Edge`, but don't add
+            // another edge to the graph.  Checking the `EdgeMap` is much
+            // cheaper than `DiGraph::update_edge` for components with many
+            // successors.
+            if self
+                .edges
+                .insert((source_idx, dest_idx), connection)
+                .is_none()
+            {
+                self.graph.add_edge(source_idx, dest_idx, ());
+            }
         }
 
         Ok(())
     }
 }
 
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node + Clone,
+    E: Edge + Clone,
+{
+    /// Returns a copy of the graph with the components with the given
+    /// `component_ids` removed, along with their connections.
+    ///
+    /// The new graph has the same site metadata as this one, and is validated
+    /// with the same config, so this
+    /// can be used to check what happens if components are taken out, for
+    /// example for maintenance.
+    ///
+    /// Returns an error if any of the given `component_ids` don't exist, or
+    /// if the resulting graph is invalid.
+    pub fn without(&self, component_ids: impl IntoIterator<Item = u64>) -> Result<Self, Error> {
+        let mut removed = HashSet::new();
+        for component_id in component_ids {
+            self.component(component_id)?;
+            removed.insert(component_id);
+        }
+
+        let components = self
+            .graph
+            .raw_nodes()
+            .iter()
+            .map(|n| &n.weight)
+            .filter(|n| !removed.contains(&n.component_id()))
+            .cloned();
+        let connections = self
+            .graph
+            .raw_edges()
+            .iter()
+            .map(|e| &self.edges[&(e.source(), e.target())])
+            .filter(|e| !removed.contains(&e.source()) && !removed.contains(&e.destination()))
+            .cloned();
+
+        Ok(
+            Self::try_new_with_config(components, connections, self.config.clone())?
+                .with_metadata(self.metadata.clone()),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ComponentCategory;
+    use crate::ErrorKind;
     use crate::InverterType;
 
     #[derive(Clone)]
@@ -129,7 +326,7 @@ mod tests {
         }
 
         fn category(&self) -> ComponentCategory {
-            self.1.clone()
+            self.1
         }
 
         fn is_supported(&self) -> bool {
@@ -204,6 +401,15 @@ mod tests {
                 == Error::invalid_component("ComponentCategory not specified for component: 9"))
         );
 
+        components.pop();
+        components.push(TestComponent(9, ComponentCategory::Other(42)));
+        assert!(
+            ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| e
+                == Error::invalid_component(
+                    "Unknown ComponentCategory Other(42) for component: 9"
+                ))
+        );
+
         components.pop();
         components.push(TestComponent(
             9,
@@ -248,7 +454,86 @@ mod tests {
                 == Error::invalid_connection("Connection:(2, 9) Can't find a component with ID 9"))
         );
 
+        connections.pop();
+        connections.push(TestConnection::new(3, 2));
+        assert!(
+            ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| e
+                == Error::invalid_connection(
+                    "Connection:(3, 2) Conflicts with the reversed connection (2, 3)."
+                ))
+        );
+
         connections.pop();
         assert!(ComponentGraph::try_new(components.clone(), connections.clone()).is_ok());
     }
+
+    #[test]
+    fn test_error_context() {
+        let (mut components, mut connections) = nodes_and_edges();
+        components.push(TestComponent(1, ComponentCategory::Grid));
+        connections.push(TestConnection(1, 2));
+
+        connections.push(TestConnection::new(2, 9));
+        let Err(err) = ComponentGraph::try_new(components.clone(), connections.clone()) else {
+            panic!("Expected an error");
+        };
+        assert_eq!(err.kind(), ErrorKind::InvalidConnection);
+        assert_eq!(err.connection(), Some((2, 9)));
+        assert_eq!(err.component_ids(), &[9]);
+        assert_eq!(err.rule(), None);
+
+        connections.pop();
+        connections.push(TestConnection::new(5, 2));
+        let Err(err) = ComponentGraph::try_new(components, connections) else {
+            panic!("Expected an error");
+        };
+        assert_eq!(err.kind(), ErrorKind::InvalidGraph);
+        assert_eq!(err.description(), "Cycle detected: 2 -> 3 -> 4 -> 5 -> 2");
+        assert_eq!(err.component_ids(), &[2, 3, 4, 5]);
+        assert_eq!(err.connection(), None);
+        assert_eq!(err.rule(), Some("validate_acyclicity::acyclic"));
+    }
+
+    #[test]
+    fn test_without() -> Result<(), Error> {
+        let (mut components, mut connections) = nodes_and_edges();
+        components.push(TestComponent(1, ComponentCategory::Grid));
+        connections.push(TestConnection(1, 2));
+        let metadata = SiteMetadata {
+            microgrid_id: Some(12),
+            ..Default::default()
+        };
+        let graph = ComponentGraph::try_new(components, connections)?.with_metadata(metadata);
+
+        let smaller = graph.without([6, 7, 8])?;
+        assert_eq!(smaller.metadata(), graph.metadata());
+        let mut ids = smaller
+            .components()
+            .map(|c| c.component_id())
+            .collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+        assert_eq!(smaller.connections().count(), 4);
+        assert_eq!(
+            smaller
+                .successors(2)?
+                .map(|c| c.component_id())
+                .collect::<Vec<_>>(),
+            vec![3]
+        );
+        // The original graph is unchanged.
+        assert_eq!(graph.components().count(), 8);
+
+        // Removing the inverter alone leaves its battery disconnected.
+        assert!(graph
+            .without([7])
+            .is_err_and(|e| e.kind() == ErrorKind::InvalidGraph));
+
+        assert_eq!(
+            graph.without([9]).err(),
+            Some(Error::component_not_found("Component with id 9 not found."))
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,314 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for converting a [`ComponentGraph`] to and from CSV.
+//!
+//! A graph is stored in two CSV files, each with a header row.  The
+//! components file has the columns `id`, `category` and the optional `name`
+//! and `external_id`, where the categories are written as they are displayed,
+//! like `SolarInverter`:
+//!
+//! ```csv
+//! id,category,name,external_id
+//! 1,Grid,,
+//! 2,Meter,Main meter,
+//! 3,SolarInverter,,a1b2
+//! ```
+//!
+//! The connections file has the columns `source` and `destination`, and the
+//! optional `rated_current` in amperes and `cable_length` in meters:
+//!
+//! ```csv
+//! source,destination,rated_current,cable_length
+//! 1,2,63,
+//! 2,3,32,120
+//! ```
+
+use std::io;
+
+use crate::{
+    ComponentCategory, ComponentGraph, ComponentGraphConfig, Edge, Error, Node, SimpleComponent,
+    SimpleConnection,
+};
+
+/// CSV export.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Writes the components and the connections of the graph as CSV to the
+    /// given writers.
+    ///
+    /// Components and connections are written in the order of their IDs.  The
+    /// `name` and `external_id` columns are left empty for components without
+    /// a [`name`][Node::name] or an [`external_id`][Node::external_id].  The
+    /// phases of connections are not written.
+    pub fn write_csv(
+        &self,
+        components: impl io::Write,
+        connections: impl io::Write,
+    ) -> Result<(), Error> {
+        let mut writer = ::csv::Writer::from_writer(components);
+        let mut rows = self.components().collect::<Vec<_>>();
+        rows.sort_by_key(|c| c.component_id());
+        writer.write_record(["id", "category", "name", "external_id"])?;
+        for component in rows {
+            writer.write_record([
+                component.component_id().to_string(),
+                component.category().to_string(),
+                component.name().unwrap_or_default().to_string(),
+                component.external_id().unwrap_or_default().to_string(),
+            ])?;
+        }
+        writer
+            .flush()
+            .map_err(|e| Error::serialization(format!("Unable to write CSV: {e}")))?;
+
+        let mut writer = ::csv::Writer::from_writer(connections);
+        let mut rows = self.connections().collect::<Vec<_>>();
+        rows.sort_by_key(|c| (c.source(), c.destination()));
+        writer.write_record(["source", "destination", "rated_current", "cable_length"])?;
+        let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+        for connection in rows {
+            writer.write_record([
+                connection.source().to_string(),
+                connection.destination().to_string(),
+                optional(connection.rated_current()),
+                optional(connection.cable_length()),
+            ])?;
+        }
+        writer
+            .flush()
+            .map_err(|e| Error::serialization(format!("Unable to write CSV: {e}")))?;
+
+        Ok(())
+    }
+}
+
+/// CSV import.
+impl ComponentGraph<SimpleComponent, SimpleConnection> {
+    /// Creates a new [`ComponentGraph`] from CSV files with its components
+    /// and connections.
+    ///
+    /// Returns an error if the files can't be parsed, or if the graph is
+    /// invalid.
+    pub fn from_csv(components: impl io::Read, connections: impl io::Read) -> Result<Self, Error> {
+        Self::from_csv_with_config(components, connections, ComponentGraphConfig::default())
+    }
+
+    /// Creates a new [`ComponentGraph`] from CSV files with its components
+    /// and connections, validating it according to the given `config`.
+    ///
+    /// Returns an error if the files can't be parsed, or if the graph is
+    /// invalid.
+    pub fn from_csv_with_config(
+        components: impl io::Read,
+        connections: impl io::Read,
+        config: ComponentGraphConfig,
+    ) -> Result<Self, Error> {
+        let components = read_rows(components, &["id", "category"], &["name", "external_id"])?
+            .into_iter()
+            .map(|(line, row)| {
+                let mut component = SimpleComponent::new(
+                    parse_id(line, "id", &row[0])?,
+                    row[1].parse::<ComponentCategory>().map_err(|e| {
+                        Error::serialization(format!("Line {line}: {}", e.description()))
+                    })?,
+                );
+                if !row[2].is_empty() {
+                    component = component.with_name(row[2].as_str());
+                }
+                if !row[3].is_empty() {
+                    component = component.with_external_id(row[3].as_str());
+                }
+                Ok(component)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let connections = read_rows(
+            connections,
+            &["source", "destination"],
+            &["rated_current", "cable_length"],
+        )?
+        .into_iter()
+        .map(|(line, row)| {
+            Ok(SimpleConnection {
+                source: parse_id(line, "source", &row[0])?,
+                destination: parse_id(line, "destination", &row[1])?,
+                rated_current: parse_optional_number(line, "rated_current", &row[2])?,
+                cable_length: parse_optional_number(line, "cable_length", &row[3])?,
+                phases: None,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+        Self::try_new_with_config(components, connections, config)
+    }
+}
+
+/// Reads the rows of a CSV file, with the values of the `required` columns
+/// followed by the values of the `optional` columns, which are empty if the
+/// column is missing.
+///
+/// Each row is returned with its line number in the file.
+fn read_rows(
+    reader: impl io::Read,
+    required: &[&str],
+    optional: &[&str],
+) -> Result<Vec<(u64, Vec<String>)>, Error> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .trim(::csv::Trim::All)
+        .from_reader(reader);
+
+    let headers = reader.headers()?.clone();
+    let position = |column: &str| headers.iter().position(|h| h == column);
+    let mut columns = Vec::new();
+    for column in required {
+        columns.push(Some(position(column).ok_or_else(|| {
+            Error::serialization(format!("Missing column: {column}"))
+        })?));
+    }
+    columns.extend(optional.iter().map(|column| position(column)));
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |p| p.line());
+        let row = columns
+            .iter()
+            .map(|column| {
+                column
+                    .and_then(|i| record.get(i))
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect();
+        rows.push((line, row));
+    }
+    Ok(rows)
+}
+
+fn parse_id(line: u64, column: &str, value: &str) -> Result<u64, Error> {
+    value
+        .parse()
+        .map_err(|_| Error::serialization(format!("Line {line}: Invalid {column}: {value:?}")))
+}
+
+/// Parses a number from an optional column, which is `None` if empty.
+fn parse_optional_number(line: u64, column: &str, value: &str) -> Result<Option<f64>, Error> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| Error::serialization(format!("Line {line}: Invalid {column}: {value:?}")))
+}
+
+impl From<::csv::Error> for Error {
+    fn from(err: ::csv::Error) -> Self {
+        Error::serialization(format!("Unable to read or write CSV: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorKind, InverterType};
+
+    #[test]
+    fn test_csv_roundtrip() -> Result<(), Error> {
+        let components = "\
+id,category,name,external_id
+1,Grid,,
+2,Meter,\"Main meter, north\",
+3,SolarInverter,,a1b2
+";
+        let connections = "\
+destination, source, rated_current
+3, 2, 32.5
+2, 1,
+";
+
+        let graph = ComponentGraph::from_csv(components.as_bytes(), connections.as_bytes())?;
+        assert_eq!(
+            graph.component(2)?.name.as_deref(),
+            Some("Main meter, north")
+        );
+        assert_eq!(
+            graph.component(3)?,
+            &SimpleComponent::new(3, ComponentCategory::Inverter(InverterType::Solar))
+                .with_external_id("a1b2")
+        );
+        assert_eq!(
+            graph.successors(1)?.map(|c| c.id).collect::<Vec<_>>(),
+            vec![2]
+        );
+
+        let mut components = Vec::new();
+        let mut connections = Vec::new();
+        graph.write_csv(&mut components, &mut connections)?;
+        assert_eq!(
+            String::from_utf8_lossy(&components),
+            concat!(
+                "id,category,name,external_id\n",
+                "1,Grid,,\n",
+                "2,Meter,\"Main meter, north\",\n",
+                "3,SolarInverter,,a1b2\n"
+            )
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&connections),
+            "source,destination,rated_current,cable_length\n1,2,,\n2,3,32.5,\n"
+        );
+
+        let reimported = ComponentGraph::from_csv(components.as_slice(), connections.as_slice())?;
+        assert_eq!(
+            reimported.component(2)?.name.as_deref(),
+            Some("Main meter, north")
+        );
+        assert_eq!(reimported.component(3)?.external_id(), Some("a1b2"));
+        assert!(reimported
+            .connections()
+            .any(|c| c.source == 2 && c.rated_current == Some(32.5)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_errors() {
+        let connections = "source,destination\n1,2\n";
+        let err = |components: &str, connections: &str| {
+            ComponentGraph::from_csv(components.as_bytes(), connections.as_bytes()).err()
+        };
+
+        assert_eq!(
+            err("id,name\n1,Grid\n", connections),
+            Some(Error::serialization("Missing column: category"))
+        );
+        assert_eq!(
+            err("id,category\n1,Grid\n2,Windmill\n", connections),
+            Some(Error::serialization(
+                "Line 3: Unknown component category: Windmill"
+            ))
+        );
+        assert_eq!(
+            err("id,category\n1,Grid\nx,Meter\n", connections),
+            Some(Error::serialization("Line 3: Invalid id: \"x\""))
+        );
+        assert_eq!(
+            err(
+                "id,category\n1,Grid\n2,Meter\n",
+                "source,destination,cable_length\n1,2,far\n"
+            ),
+            Some(Error::serialization(
+                "Line 2: Invalid cable_length: \"far\""
+            ))
+        );
+        assert_eq!(
+            err("id,category\n1,Grid\n2,Battery\n", connections).map(|e| e.kind()),
+            Some(ErrorKind::InvalidGraph)
+        );
+    }
+}
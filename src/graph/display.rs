@@ -0,0 +1,194 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! `Display` and `Debug` implementations for [`ComponentGraph`].
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::{ComponentGraph, Edge, Node};
+
+/// Renders the graph as an indented tree, starting from the root.
+///
+/// Components with multiple predecessors are shown under each of them, but
+/// their successors are only shown the first time, and later occurrences are
+/// marked with `(see above)`.  For example:
+///
+/// ```text
+/// Grid:1
+/// └── Meter:2 (Grid meter)
+///     ├── Meter:3 (PV meter)
+///     │   └── SolarInverter:4
+///     └── Meter:5 (Battery meter)
+///         ├── BatteryInverter:6
+///         │   └── Battery:8
+///         └── BatteryInverter:7
+///             └── Battery:8
+/// ```
+impl<N, E> fmt::Display for ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut visited = HashSet::new();
+        self.fmt_subtree(f, self.root_id, "", "", &mut visited)
+    }
+}
+
+/// With the alternate flag (`{:#?}`), renders the graph as a tree, like
+/// `Display`.  Otherwise, gives a one-line summary.
+impl<N, E> fmt::Debug for ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            writeln!(f, "ComponentGraph")?;
+            return fmt::Display::fmt(self, f);
+        }
+        f.debug_struct("ComponentGraph")
+            .field("root_id", &self.root_id)
+            .field("components", &self.graph.node_count())
+            .field("connections", &self.graph.edge_count())
+            .finish()
+    }
+}
+
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Writes the line for the given component, followed by its successors.
+    ///
+    /// `prefix` is written before the component itself, and `indent` before
+    /// the lines of its successors.
+    fn fmt_subtree(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        component_id: u64,
+        prefix: &str,
+        indent: &str,
+        visited: &mut HashSet<u64>,
+    ) -> fmt::Result {
+        let Ok(component) = self.component(component_id) else {
+            return Err(fmt::Error);
+        };
+        write!(f, "{prefix}{}:{component_id}", component.category())?;
+        if let Ok(Some(role)) = self.meter_role(component_id) {
+            write!(f, " ({role} meter)")?;
+        }
+
+        let Ok(successors) = self.successors(component_id) else {
+            return Err(fmt::Error);
+        };
+        let successors = successors.map(|s| s.component_id()).collect::<Vec<_>>();
+        if !visited.insert(component_id) {
+            if !successors.is_empty() {
+                write!(f, " (see above)")?;
+            }
+            return writeln!(f);
+        }
+        writeln!(f)?;
+
+        for (index, &successor) in successors.iter().enumerate() {
+            let (branch, continuation) = if index + 1 == successors.len() {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            self.fmt_subtree(
+                f,
+                successor,
+                &format!("{indent}{branch}"),
+                &format!("{indent}{continuation}"),
+                visited,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentCategory, Error, InverterType};
+
+    #[derive(Clone)]
+    struct TestComponent(u64, ComponentCategory);
+
+    impl Node for TestComponent {
+        fn component_id(&self) -> u64 {
+            self.0
+        }
+
+        fn category(&self) -> ComponentCategory {
+            self.1
+        }
+
+        fn is_supported(&self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Clone)]
+    struct TestConnection(u64, u64);
+
+    impl Edge for TestConnection {
+        fn source(&self) -> u64 {
+            self.0
+        }
+
+        fn destination(&self) -> u64 {
+            self.1
+        }
+    }
+
+    #[test]
+    fn test_display() -> Result<(), Error> {
+        let graph = ComponentGraph::try_new(
+            vec![
+                TestComponent(1, ComponentCategory::Grid),
+                TestComponent(2, ComponentCategory::Meter),
+                TestComponent(3, ComponentCategory::Meter),
+                TestComponent(4, ComponentCategory::Inverter(InverterType::Solar)),
+                TestComponent(5, ComponentCategory::Meter),
+                TestComponent(6, ComponentCategory::Inverter(InverterType::Battery)),
+                TestComponent(7, ComponentCategory::Inverter(InverterType::Battery)),
+                TestComponent(8, ComponentCategory::Battery),
+            ],
+            vec![
+                TestConnection(1, 2),
+                TestConnection(2, 3),
+                TestConnection(3, 4),
+                TestConnection(2, 5),
+                TestConnection(5, 6),
+                TestConnection(5, 7),
+                TestConnection(6, 8),
+                TestConnection(7, 8),
+            ],
+        )?;
+
+        let tree = "\
+Grid:1
+└── Meter:2 (Grid meter)
+    ├── Meter:3 (PV meter)
+    │   └── SolarInverter:4
+    └── Meter:5 (Battery meter)
+        ├── BatteryInverter:6
+        │   └── Battery:8
+        └── BatteryInverter:7
+            └── Battery:8
+";
+        assert_eq!(graph.to_string(), tree);
+        assert_eq!(format!("{graph:#?}"), format!("ComponentGraph\n{tree}"));
+        assert_eq!(
+            format!("{graph:?}"),
+            "ComponentGraph { root_id: 1, components: 8, connections: 8 }"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for splitting a [`ComponentGraph`] into the feeders beneath the
+//! grid connection.
+
+use crate::{ComponentCategory, ComponentGraph, Edge, Error, InverterType, Node};
+
+/// The kind of components a [`Feeder`] supplies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FeederRole {
+    /// A feeder with only batteries and battery inverters.
+    Battery,
+    /// A feeder with only PV inverters.
+    Pv,
+    /// A feeder with only wind inverters.
+    Wind,
+    /// A feeder with only EV chargers.
+    EvCharger,
+    /// A feeder with only CHPs.
+    Chp,
+    /// A feeder with components of more than one of the above kinds, or with
+    /// other components, like hybrid inverters or loads.
+    Mixed,
+    /// A feeder without any producers or consumers, for example with only
+    /// meters.
+    Empty,
+}
+
+/// The components beneath one successor of the grid, returned by
+/// [`ComponentGraph::feeders`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Feeder {
+    /// The ID of the grid successor at the top of the feeder.
+    pub root_id: u64,
+    /// The IDs of the components in the feeder, starting with the root,
+    /// followed by the components below it in breadth-first order.
+    pub component_ids: Vec<u64>,
+    /// The kind of components the feeder supplies.
+    pub role: FeederRole,
+}
+
+/// Feeder segmentation.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns one [`Feeder`] for each successor of the grid, in the order of
+    /// their component IDs.
+    ///
+    /// Sensors beneath the grid are not feeders, and the grid fuse is looked
+    /// through, so that the feeders are the successors of the fuse.
+    /// Components reachable from multiple feeders are listed in each of them.
+    pub fn feeders(&self) -> Result<Vec<Feeder>, Error> {
+        let mut roots = vec![];
+        for successor in self.successors(self.root_id)? {
+            if successor.category().is_fuse() {
+                roots.extend(self.successors(successor.component_id())?);
+            } else if !successor.category().is_sensor() {
+                roots.push(successor);
+            }
+        }
+        roots.sort_by_key(|n| n.component_id());
+
+        roots
+            .into_iter()
+            .map(|root| {
+                let components = std::iter::once(root)
+                    .chain(self.descendants(root.component_id(), None)?)
+                    .collect::<Vec<_>>();
+                Ok(Feeder {
+                    root_id: root.component_id(),
+                    component_ids: components.iter().map(|n| n.component_id()).collect(),
+                    role: feeder_role(components.iter().map(|n| n.category())),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Returns the role of a feeder with components of the given categories.
+fn feeder_role(categories: impl IntoIterator<Item = ComponentCategory>) -> FeederRole {
+    let mut role = FeederRole::Empty;
+    for category in categories {
+        let kind = match category {
+            ComponentCategory::Meter
+            | ComponentCategory::Fuse
+            | ComponentCategory::Relay
+            | ComponentCategory::Precharger
+            | ComponentCategory::Converter
+            | ComponentCategory::VoltageTransformer
+            | ComponentCategory::Sensor
+            | ComponentCategory::PvString => continue,
+            ComponentCategory::Battery
+            | ComponentCategory::BatteryModule
+            | ComponentCategory::Inverter(InverterType::Battery) => FeederRole::Battery,
+            ComponentCategory::Inverter(InverterType::Solar) => FeederRole::Pv,
+            ComponentCategory::Inverter(InverterType::Wind) => FeederRole::Wind,
+            ComponentCategory::EvCharger(_) => FeederRole::EvCharger,
+            ComponentCategory::Chp => FeederRole::Chp,
+            _ => FeederRole::Mixed,
+        };
+        role = match role {
+            FeederRole::Empty => kind,
+            role if role == kind => role,
+            _ => FeederRole::Mixed,
+        };
+    }
+    role
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::ComponentGraphBuilder;
+
+    #[test]
+    fn test_feeders() -> Result<(), Error> {
+        let mut builder = ComponentGraphBuilder::new();
+        let grid = builder.grid();
+        let bat_chain = builder.meter_bat_chain(1, 1);
+        let pv_chain = builder.meter_pv_chain(2);
+        let mixed_meter = builder.meter();
+        let ev_chain = builder.meter_ev_charger_chain(1);
+        let chp_chain = builder.meter_chp_chain(1);
+        builder
+            .connect(grid, bat_chain)
+            .connect(grid, pv_chain)
+            .connect(grid, mixed_meter)
+            .connect(mixed_meter, ev_chain)
+            .connect(mixed_meter, chp_chain);
+        let graph = builder.build()?;
+        // Grid:1 -> Meter:2 -> BatteryInverter:3 -> Battery:4
+        //        -> Meter:5 -> SolarInverter:6,7
+        //        -> Meter:8 -> Meter:9 -> ACEVCharger:10
+        //                   -> Meter:11 -> CHP:12
+
+        assert_eq!(
+            graph.feeders()?,
+            [
+                Feeder {
+                    root_id: 2,
+                    component_ids: vec![2, 3, 4],
+                    role: FeederRole::Battery,
+                },
+                Feeder {
+                    root_id: 5,
+                    component_ids: vec![5, 6, 7],
+                    role: FeederRole::Pv,
+                },
+                Feeder {
+                    root_id: 8,
+                    component_ids: vec![8, 9, 11, 10, 12],
+                    role: FeederRole::Mixed,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for rendering a [`ComponentGraph`] as a standalone HTML page.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use petgraph::algo::toposort;
+
+use crate::{ComponentCategory, ComponentGraph, Edge, Error, Node};
+
+/// Horizontal and vertical distance between the centers of two components in
+/// the rendered graph, in pixels.
+const SPACING: usize = 120;
+
+/// The radius of the circles that represent the components, in pixels.
+const RADIUS: usize = 24;
+
+/// The page template, with the placeholders `{title}`, `{width}`, `{height}`,
+/// `{edges}` and `{nodes}`.
+const TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body { font-family: sans-serif; margin: 16px; }
+.edge { stroke: #999; stroke-width: 2; }
+.edge.active { stroke: #000; stroke-width: 3; }
+.node circle { stroke: #333; stroke-width: 1.5; cursor: pointer; }
+.node.active circle { stroke-width: 4; }
+.node text { font-size: 11px; text-anchor: middle; pointer-events: none; }
+#tooltip { position: absolute; display: none; padding: 6px 8px; background: #fff;
+  border: 1px solid #333; border-radius: 4px; font-size: 12px; white-space: pre; }
+</style>
+</head>
+<body>
+<h3>{title}</h3>
+<svg width="{width}" height="{height}">
+{edges}{nodes}</svg>
+<div id="tooltip"></div>
+<script>
+const tooltip = document.getElementById("tooltip");
+document.querySelectorAll(".node").forEach((node) => {
+  const id = node.dataset.id;
+  const connected = document.querySelectorAll(
+    `.edge[data-source="${id}"], .edge[data-destination="${id}"]`);
+  node.addEventListener("mouseenter", () => {
+    node.classList.add("active");
+    connected.forEach((edge) => edge.classList.add("active"));
+    tooltip.textContent = node.dataset.tooltip;
+    tooltip.style.display = "block";
+  });
+  node.addEventListener("mousemove", (event) => {
+    tooltip.style.left = `${event.pageX + 12}px`;
+    tooltip.style.top = `${event.pageY + 12}px`;
+  });
+  node.addEventListener("mouseleave", () => {
+    node.classList.remove("active");
+    connected.forEach((edge) => edge.classList.remove("active"));
+    tooltip.style.display = "none";
+  });
+});
+</script>
+</body>
+</html>
+"#;
+
+/// HTML export.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns a standalone HTML page with an interactive rendering of the
+    /// graph.
+    ///
+    /// Components are drawn in layers below the grid and colored by their
+    /// category.  Hovering over a component highlights its connections and
+    /// shows a tooltip with its details, including the role of meters.
+    pub fn to_html(&self) -> Result<String, Error> {
+        let levels = self.levels()?;
+
+        let mut layers: Vec<Vec<&N>> = vec![];
+        for component in self.components() {
+            let level = levels[&component.component_id()];
+            if layers.len() <= level {
+                layers.resize(level + 1, vec![]);
+            }
+            layers[level].push(component);
+        }
+
+        let mut positions = HashMap::new();
+        for (level, layer) in layers.iter_mut().enumerate() {
+            layer.sort_by_key(|c| c.component_id());
+            for (index, component) in layer.iter().enumerate() {
+                positions.insert(
+                    component.component_id(),
+                    ((index + 1) * SPACING, (level + 1) * SPACING),
+                );
+            }
+        }
+        let width = (layers.iter().map(Vec::len).max().unwrap_or(0) + 1) * SPACING;
+        let height = (layers.len() + 1) * SPACING;
+
+        let mut connections = self
+            .connections()
+            .map(|c| (c.source(), c.destination()))
+            .collect::<Vec<_>>();
+        connections.sort();
+        let mut edges = String::new();
+        for (source, destination) in connections {
+            let (x1, y1) = positions[&source];
+            let (x2, y2) = positions[&destination];
+            // Infallible, writing to a `String` can't fail.
+            let _ = writeln!(
+                edges,
+                r#"<line class="edge" data-source="{source}" data-destination="{destination}" x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}"/>"#
+            );
+        }
+
+        let mut nodes = String::new();
+        for component in layers.iter().flatten() {
+            let cid = component.component_id();
+            let category = component.category();
+            let (x, y) = positions[&cid];
+            let mut tooltip = format!("{category}:{cid}");
+            if let Some(role) = self.meter_role(cid)? {
+                tooltip.push_str(&format!("\nRole: {role}"));
+            }
+            if !component.is_supported() {
+                tooltip.push_str("\nNot supported");
+            }
+            if let Some(rated_power) = component.rated_power() {
+                tooltip.push_str(&format!("\nRated power: {rated_power} W"));
+            }
+            let _ = writeln!(
+                nodes,
+                concat!(
+                    r#"<g class="node" data-id="{cid}" data-tooltip="{tooltip}">"#,
+                    r#"<circle cx="{x}" cy="{y}" r="{radius}" fill="{color}"/>"#,
+                    r#"<text x="{x}" y="{y}" dy="4">{cid}</text>"#,
+                    r#"<text x="{x}" y="{label_y}">{category}</text></g>"#
+                ),
+                cid = cid,
+                tooltip = tooltip.replace('\n', "&#10;"),
+                x = x,
+                y = y,
+                label_y = y + RADIUS + 14,
+                radius = RADIUS,
+                color = category_color(category),
+                category = category,
+            );
+        }
+
+        Ok(TEMPLATE
+            .replace(
+                "{title}",
+                &format!("Component graph of {} components", positions.len()),
+            )
+            .replace("{width}", &width.to_string())
+            .replace("{height}", &height.to_string())
+            .replace("{edges}", &edges)
+            .replace("{nodes}", &nodes))
+    }
+
+    /// Returns the level of each component, which is the length of the
+    /// longest path from the root to it, so that all connections point
+    /// downwards.
+    fn levels(&self) -> Result<HashMap<u64, usize>, Error> {
+        let order = toposort(&self.graph, None)
+            .map_err(|_| Error::internal("Unable to sort the components of a cyclic graph."))?;
+        let mut levels = HashMap::new();
+        for index in order {
+            let level = self
+                .graph
+                .neighbors_directed(index, petgraph::Direction::Incoming)
+                .map(|p| levels[&self.graph[p].component_id()] + 1)
+                .max()
+                .unwrap_or(0);
+            levels.insert(self.graph[index].component_id(), level);
+        }
+        Ok(levels)
+    }
+}
+
+/// Returns the fill color for components of the given category.
+fn category_color(category: ComponentCategory) -> &'static str {
+    match category {
+        ComponentCategory::Grid => "#9e9e9e",
+        ComponentCategory::Meter => "#90caf9",
+        ComponentCategory::Battery => "#a5d6a7",
+        ComponentCategory::Inverter(_) => "#ffe082",
+        ComponentCategory::EvCharger(_) => "#ce93d8",
+        ComponentCategory::Chp => "#ffab91",
+        ComponentCategory::Converter
+        | ComponentCategory::Fuse
+        | ComponentCategory::Precharger
+        | ComponentCategory::Relay
+        | ComponentCategory::VoltageTransformer => "#e0e0e0",
+        ComponentCategory::CryptoMiner
+        | ComponentCategory::Electrolyzer
+        | ComponentCategory::Hvac
+        | ComponentCategory::PvString
+        | ComponentCategory::BatteryModule
+        | ComponentCategory::Sensor
+        | ComponentCategory::Other(_)
+        | ComponentCategory::Unspecified => "#ffffff",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InverterType;
+
+    #[derive(Clone)]
+    struct TestComponent(u64, ComponentCategory);
+
+    impl Node for TestComponent {
+        fn component_id(&self) -> u64 {
+            self.0
+        }
+
+        fn category(&self) -> ComponentCategory {
+            self.1
+        }
+
+        fn is_supported(&self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Clone)]
+    struct TestConnection(u64, u64);
+
+    impl Edge for TestConnection {
+        fn source(&self) -> u64 {
+            self.0
+        }
+
+        fn destination(&self) -> u64 {
+            self.1
+        }
+    }
+
+    #[test]
+    fn test_to_html() -> Result<(), Error> {
+        let graph = ComponentGraph::try_new(
+            vec![
+                TestComponent(1, ComponentCategory::Grid),
+                TestComponent(2, ComponentCategory::Meter),
+                TestComponent(3, ComponentCategory::Meter),
+                TestComponent(4, ComponentCategory::Inverter(InverterType::Solar)),
+            ],
+            vec![
+                TestConnection(1, 2),
+                TestConnection(2, 3),
+                TestConnection(3, 4),
+            ],
+        )?;
+
+        let html = graph.to_html()?;
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Component graph of 4 components</title>"));
+        assert_eq!(html.matches(r#"<g class="node""#).count(), 4);
+        assert_eq!(html.matches(r#"<line class="edge""#).count(), 3);
+        assert!(html.contains(
+            r#"<line class="edge" data-source="3" data-destination="4" x1="120" y1="360" x2="120" y2="480"/>"#
+        ));
+        assert!(html.contains(r#"data-id="2" data-tooltip="Meter:2&#10;Role: Grid""#));
+        assert!(html.contains(r#"data-id="3" data-tooltip="Meter:3&#10;Role: PV""#));
+        assert!(html.contains(
+            r##"data-tooltip="SolarInverter:4"><circle cx="120" cy="480" r="24" fill="#ffe082"/>"##
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,238 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Checks for the invariants of the components in a [`ComponentGraph`].
+//!
+//! These are the building blocks of the validation done when a graph is
+//! created, and can be reused for site-specific rules on top of it.  Each
+//! check returns an [`Error`] of kind
+//! [`InvalidGraph`][crate::ErrorKind::InvalidGraph] for the first violation
+//! it finds, with the offending components and the name of the check as its
+//! [`rule`][Error::rule]:
+//!
+//! ```
+//! # use component_graph::{builder::ComponentGraphBuilder, invariants, *};
+//! # let mut builder = ComponentGraphBuilder::new();
+//! # let grid = builder.grid();
+//! # let meter = builder.meter();
+//! # let pv_chain = builder.meter_pv_chain(1);
+//! # builder.connect(grid, meter).connect(meter, pv_chain);
+//! # let graph = builder.build()?;
+//! // Site rule: the grid meter must only have meters as successors.
+//! let grid_meter = graph.component(2)?;
+//! invariants::ensure_successor_categories(&graph, grid_meter, &[ComponentCategory::Meter])?;
+//!
+//! let err = invariants::ensure_leaf(&graph, grid_meter).unwrap_err();
+//! assert_eq!(err.description(), "Meter:2 can't have any successors. Found Meter:3.");
+//! assert_eq!(err.rule(), Some("leaf"));
+//! # Ok::<(), Error>(())
+//! ```
+//!
+//! Categories are compared after mapping them with
+//! [`ComponentGraphConfig::effective_category`][crate::ComponentGraphConfig::effective_category],
+//! so that inverters with an unspecified type are checked as the configured
+//! type.  [`ensure_predecessor_categories`] and [`ensure_allowed_successors`]
+//! also honor the configured
+//! [`successor_rules`][crate::ComponentGraphConfig::successor_rules].  The
+//! transparency settings are not applied, so transparent components like
+//! converters are checked like any other component.
+
+use crate::{ComponentCategory, ComponentGraph, Edge, Error, Node};
+
+/// Checks that the given node is a leaf node.
+pub fn ensure_leaf<N: Node, E: Edge>(cg: &ComponentGraph<N, E>, node: &N) -> Result<(), Error> {
+    if let Some(successor) = cg.successors(node.component_id())?.next() {
+        return Err(Error::invalid_graph(format!(
+            "{}:{} can't have any successors. Found {}:{}.",
+            node.category(),
+            node.component_id(),
+            successor.category(),
+            successor.component_id()
+        ))
+        .with_components([node.component_id(), successor.component_id()])
+        .with_rule("leaf"));
+    }
+    Ok(())
+}
+
+/// Checks that the given node is *not* a leaf node.
+pub fn ensure_not_leaf<N: Node, E: Edge>(cg: &ComponentGraph<N, E>, node: &N) -> Result<(), Error> {
+    if cg.successors(node.component_id())?.next().is_none() {
+        return Err(Error::invalid_graph(format!(
+            "{}:{} must have at least one successor.",
+            node.category(),
+            node.component_id()
+        ))
+        .with_components([node.component_id()])
+        .with_rule("not_leaf"));
+    }
+    Ok(())
+}
+
+/// Checks that the given node is a root node.
+pub fn ensure_root<N: Node, E: Edge>(cg: &ComponentGraph<N, E>, node: &N) -> Result<(), Error> {
+    if let Some(predecessor) = cg.predecessors(node.component_id())?.next() {
+        return Err(Error::invalid_graph(format!(
+            "{}:{} can't have any predecessors. Found {}:{}.",
+            node.category(),
+            node.component_id(),
+            predecessor.category(),
+            predecessor.component_id()
+        ))
+        .with_components([node.component_id(), predecessor.component_id()])
+        .with_rule("root"));
+    }
+    Ok(())
+}
+
+/// Checks that the given node only has predecessors with the given
+/// categories, or with categories that the config allows to have the
+/// category of the node as successors.
+pub fn ensure_predecessor_categories<N: Node, E: Edge>(
+    cg: &ComponentGraph<N, E>,
+    node: &N,
+    categories: &[ComponentCategory],
+) -> Result<(), Error> {
+    let mut categories = categories.to_vec();
+    categories.extend(
+        cg.config
+            .extra_predecessors(cg.config.effective_category(node.category())),
+    );
+    for predecessor in cg.predecessors(node.component_id())? {
+        if !categories.contains(&cg.config.effective_category(predecessor.category())) {
+            return Err(Error::invalid_graph(format!(
+                "{}:{} can only have predecessors with categories: [{}]. Found {}:{}.",
+                node.category(),
+                node.component_id(),
+                categories
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                predecessor.category(),
+                predecessor.component_id()
+            ))
+            .with_components([node.component_id(), predecessor.component_id()])
+            .with_rule("predecessor_categories"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the given node only has successors with the categories
+/// configured for its category, or with the given `builtin` categories
+/// if none are configured.  If there are no allowed categories, the node
+/// must be a leaf.
+pub fn ensure_allowed_successors<N: Node, E: Edge>(
+    cg: &ComponentGraph<N, E>,
+    node: &N,
+    builtin: &[ComponentCategory],
+) -> Result<(), Error> {
+    let category = cg.config.effective_category(node.category());
+    match cg.config.allowed_successors(category, builtin) {
+        [] => ensure_leaf(cg, node),
+        allowed => ensure_successor_categories(cg, node, allowed),
+    }
+}
+
+/// Checks that the given node only has successors with the given categories.
+pub fn ensure_successor_categories<N: Node, E: Edge>(
+    cg: &ComponentGraph<N, E>,
+    node: &N,
+    categories: &[ComponentCategory],
+) -> Result<(), Error> {
+    for successor in cg.successors(node.component_id())? {
+        if !categories.contains(&cg.config.effective_category(successor.category())) {
+            return Err(Error::invalid_graph(format!(
+                "{}:{} can only have successors with categories [{}]. Found {}:{}.",
+                node.category(),
+                node.component_id(),
+                categories
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                successor.category(),
+                successor.component_id()
+            ))
+            .with_components([node.component_id(), successor.component_id()])
+            .with_rule("successor_categories"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the given node does not have any successors with the given categories.
+pub fn ensure_successor_not_categories<N: Node, E: Edge>(
+    cg: &ComponentGraph<N, E>,
+    node: &N,
+    categories: &[ComponentCategory],
+) -> Result<(), Error> {
+    for successor in cg.successors(node.component_id())? {
+        if categories.contains(&cg.config.effective_category(successor.category())) {
+            return Err(Error::invalid_graph(format!(
+                "{}:{} can't have successors with categories [{}]. Found {}:{}.",
+                node.category(),
+                node.component_id(),
+                categories
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                successor.category(),
+                successor.component_id()
+            ))
+            .with_components([node.component_id(), successor.component_id()])
+            .with_rule("successor_not_categories"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the given node's successors are exclusive to it.
+///
+/// A node's successors are exclusive to the node if they don't have any
+/// other predecessors.
+pub fn ensure_exclusive_successors<N: Node, E: Edge>(
+    cg: &ComponentGraph<N, E>,
+    node: &N,
+) -> Result<(), Error> {
+    for successor in cg.successors(node.component_id())? {
+        if cg.predecessors(successor.component_id())?.count() > 1 {
+            return Err(Error::invalid_graph(format!(
+                "{}:{} can't have successors with multiple predecessors. Found {}:{}.",
+                node.category(),
+                node.component_id(),
+                successor.category(),
+                successor.component_id()
+            ))
+            .with_components([node.component_id(), successor.component_id()])
+            .with_rule("exclusive_successors"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the given node's predecessors are exclusive to it.
+///
+/// A node's predecessors are exclusive to the node if they don't have any
+/// other successors.
+pub fn ensure_exclusive_predecessors<N: Node, E: Edge>(
+    cg: &ComponentGraph<N, E>,
+    node: &N,
+) -> Result<(), Error> {
+    for predecessor in cg.predecessors(node.component_id())? {
+        if cg.successors(predecessor.component_id())?.count() > 1 {
+            return Err(Error::invalid_graph(format!(
+                "{}:{} can't have predecessors with multiple successors. Found {}:{}.",
+                node.category(),
+                node.component_id(),
+                predecessor.category(),
+                predecessor.component_id()
+            ))
+            .with_components([node.component_id(), predecessor.component_id()])
+            .with_rule("exclusive_predecessors"));
+        }
+    }
+    Ok(())
+}
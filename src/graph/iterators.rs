@@ -2,11 +2,19 @@
 // Copyright © 2024 Frequenz Energy-as-a-Service GmbH
 
 //! Iterators over components and connections in a `ComponentGraph`.
+//!
+//! All iterators, except for [`Descendants`], know their exact length, and
+//! can be iterated from both ends.
 
-use petgraph::graph::DiGraph;
+use std::collections::{HashSet, VecDeque};
+use std::iter::FusedIterator;
+
+use petgraph::graph::{DiGraph, NodeIndex};
 
 use crate::{ComponentGraph, Edge, Node};
 
+use super::Adjacency;
+
 /// An iterator over the components in a `ComponentGraph`.
 pub struct Components<'a, N>
 where
@@ -24,8 +32,25 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         self.iter.next().map(|n| &n.weight)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<N> DoubleEndedIterator for Components<'_, N>
+where
+    N: Node,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|n| &n.weight)
+    }
 }
 
+impl<N> ExactSizeIterator for Components<'_, N> where N: Node {}
+
+impl<N> FusedIterator for Components<'_, N> where N: Node {}
+
 /// An iterator over the connections in a `ComponentGraph`.
 pub struct Connections<'a, N, E>
 where
@@ -48,15 +73,114 @@ where
             .next()
             .and_then(|e| self.cg.edges.get(&(e.source(), e.target())))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Every edge in the graph has an entry in the `EdgeMap`.
+        self.iter.size_hint()
+    }
+}
+
+impl<N, E> DoubleEndedIterator for Connections<'_, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next_back()
+            .and_then(|e| self.cg.edges.get(&(e.source(), e.target())))
+    }
+}
+
+impl<N, E> ExactSizeIterator for Connections<'_, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+}
+
+impl<N, E> FusedIterator for Connections<'_, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+}
+
+/// An iterator over the connections in a `ComponentGraph`, together with
+/// their source and destination components.
+pub struct ConnectionsWithNodes<'a, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    pub(crate) cg: &'a ComponentGraph<N, E>,
+    pub(crate) iter: std::slice::Iter<'a, petgraph::graph::Edge<()>>,
+}
+
+impl<'a, N, E> ConnectionsWithNodes<'a, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    fn triple(&self, edge: &petgraph::graph::Edge<()>) -> Option<(&'a N, &'a E, &'a N)> {
+        let (source, target) = (edge.source(), edge.target());
+        let connection = self.cg.edges.get(&(source, target))?;
+        Some((&self.cg.graph[source], connection, &self.cg.graph[target]))
+    }
+}
+
+impl<'a, N, E> Iterator for ConnectionsWithNodes<'a, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    type Item = (&'a N, &'a E, &'a N);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let edge = self.iter.next()?;
+        self.triple(edge)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<N, E> DoubleEndedIterator for ConnectionsWithNodes<'_, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let edge = self.iter.next_back()?;
+        self.triple(edge)
+    }
+}
+
+impl<N, E> ExactSizeIterator for ConnectionsWithNodes<'_, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+}
+
+impl<N, E> FusedIterator for ConnectionsWithNodes<'_, N, E>
+where
+    N: Node,
+    E: Edge,
+{
 }
 
 /// An iterator over the neighbors of a component in a `ComponentGraph`.
+///
+/// Neighbors are always returned in the order of their component IDs, no
+/// matter in which order the connections were given.
 pub struct Neighbors<'a, N>
 where
     N: Node,
 {
     pub(crate) graph: &'a DiGraph<N, ()>,
-    pub(crate) iter: petgraph::graph::Neighbors<'a, ()>,
+    pub(crate) iter: std::slice::Iter<'a, NodeIndex>,
 }
 
 impl<'a, N> Iterator for Neighbors<'a, N>
@@ -66,6 +190,62 @@ where
     type Item = &'a N;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next().map(|i| &self.graph[i])
+        self.iter.next().map(|&i| &self.graph[i])
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<N> DoubleEndedIterator for Neighbors<'_, N>
+where
+    N: Node,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|&i| &self.graph[i])
+    }
+}
+
+impl<N> ExactSizeIterator for Neighbors<'_, N> where N: Node {}
+
+impl<N> FusedIterator for Neighbors<'_, N> where N: Node {}
+
+/// A breadth-first iterator over the components below a component in a
+/// `ComponentGraph`.
+///
+/// Components are returned in the order of their distance from the starting
+/// component, and each component is only returned once, even if it can be
+/// reached over multiple paths.
+pub struct Descendants<'a, N>
+where
+    N: Node,
+{
+    pub(crate) graph: &'a DiGraph<N, ()>,
+    pub(crate) adjacency: &'a [Adjacency],
+    /// The nodes to visit, with their distance from the starting node.
+    pub(crate) queue: VecDeque<(NodeIndex, usize)>,
+    pub(crate) visited: HashSet<NodeIndex>,
+    pub(crate) max_depth: Option<usize>,
 }
+
+impl<'a, N> Iterator for Descendants<'a, N>
+where
+    N: Node,
+{
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, depth) = self.queue.pop_front()?;
+        if self.max_depth.is_none_or(|max_depth| depth < max_depth) {
+            for &successor in &self.adjacency[index.index()].successors {
+                if self.visited.insert(successor) {
+                    self.queue.push_back((successor, depth + 1));
+                }
+            }
+        }
+        Some(&self.graph[index])
+    }
+}
+
+impl<N> FusedIterator for Descendants<'_, N> where N: Node {}
@@ -0,0 +1,220 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for converting a [`ComponentGraph`] to and from JSON.
+//!
+//! The JSON schema has a list of components and a list of connections, and
+//! optionally the site metadata:
+//!
+//! ```json
+//! {
+//!   "metadata": { "microgrid_id": 12, "timezone": "Europe/Berlin" },
+//!   "components": [
+//!     { "id": 1, "category": "Grid" },
+//!     { "id": 2, "category": "Meter", "name": "Main meter" },
+//!     { "id": 3, "category": { "Inverter": "Solar" } }
+//!   ],
+//!   "connections": [
+//!     { "source": 1, "destination": 2 },
+//!     { "source": 2, "destination": 3 }
+//!   ]
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ComponentGraph, ComponentGraphConfig, Edge, Error, Node, SimpleComponent, SimpleConnection,
+    SiteMetadata,
+};
+
+/// The JSON representation of a graph.
+#[derive(Serialize, Deserialize)]
+struct GraphData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<SiteMetadata>,
+    components: Vec<SimpleComponent>,
+    connections: Vec<SimpleConnection>,
+}
+
+/// JSON export.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns a JSON representation of the graph.
+    ///
+    /// Components and connections are written in the order of their IDs, so
+    /// that the output is stable across calls.
+    ///
+    /// The names and external IDs of the components, and the ratings and
+    /// phases of the connections are included when the components and
+    /// connections provide them.
+    pub fn to_json(&self) -> Result<String, Error> {
+        let mut components = self
+            .components()
+            .map(|c| SimpleComponent {
+                name: c.name().map(str::to_string),
+                external_id: c.external_id().map(str::to_string),
+                ..SimpleComponent::new(c.component_id(), c.category())
+            })
+            .collect::<Vec<_>>();
+        components.sort_by_key(|c| c.id);
+
+        let mut connections = self
+            .connections()
+            .map(|c| SimpleConnection {
+                rated_current: c.rated_current(),
+                cable_length: c.cable_length(),
+                phases: c.phases(),
+                ..SimpleConnection::new(c.source(), c.destination())
+            })
+            .collect::<Vec<_>>();
+        connections.sort_by_key(|c| (c.source, c.destination));
+
+        let metadata =
+            (self.metadata() != &SiteMetadata::default()).then(|| self.metadata().clone());
+
+        serde_json::to_string(&GraphData {
+            metadata,
+            components,
+            connections,
+        })
+        .map_err(|e| Error::serialization(format!("Unable to serialize graph: {e}")))
+    }
+}
+
+/// JSON import.
+impl ComponentGraph<SimpleComponent, SimpleConnection> {
+    /// Creates a new [`ComponentGraph`] from its JSON representation.
+    ///
+    /// Returns an error if the JSON can't be parsed, or if the graph is
+    /// invalid.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        Self::from_json_with_config(json, ComponentGraphConfig::default())
+    }
+
+    /// Creates a new [`ComponentGraph`] from its JSON representation,
+    /// validating it according to the given `config`.
+    ///
+    /// Returns an error if the JSON can't be parsed, or if the graph is
+    /// invalid.
+    pub fn from_json_with_config(json: &str, config: ComponentGraphConfig) -> Result<Self, Error> {
+        let data: GraphData = serde_json::from_str(json)
+            .map_err(|e| Error::serialization(format!("Unable to parse graph: {e}")))?;
+        Ok(
+            Self::try_new_with_config(data.components, data.connections, config)?
+                .with_metadata(data.metadata.unwrap_or_default()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentCategory, ErrorKind, InverterType};
+
+    #[test]
+    fn test_json_roundtrip() -> Result<(), Error> {
+        let json = r#"{
+            "components": [
+                { "id": 1, "category": "Grid" },
+                { "id": 2, "category": "Meter", "name": "Main meter" },
+                { "id": 3, "category": { "Inverter": "Solar" }, "external_id": "a1b2" }
+            ],
+            "connections": [
+                { "source": 2, "destination": 3, "rated_current": 32.5, "phases": "Three" },
+                { "source": 1, "destination": 2 }
+            ]
+        }"#;
+
+        let graph = ComponentGraph::from_json(json)?;
+        assert_eq!(
+            graph.component(2)?,
+            &SimpleComponent {
+                id: 2,
+                category: ComponentCategory::Meter,
+                name: Some("Main meter".to_string()),
+                external_id: None,
+            }
+        );
+        assert_eq!(
+            graph.component(3)?,
+            &SimpleComponent::new(3, ComponentCategory::Inverter(InverterType::Solar))
+                .with_external_id("a1b2")
+        );
+
+        let exported = graph.to_json()?;
+        assert_eq!(
+            exported,
+            concat!(
+                r#"{"components":["#,
+                r#"{"id":1,"category":"Grid"},"#,
+                r#"{"id":2,"category":"Meter","name":"Main meter"},"#,
+                r#"{"id":3,"category":{"Inverter":"Solar"},"external_id":"a1b2"}],"#,
+                r#""connections":["#,
+                r#"{"source":1,"destination":2},"#,
+                r#"{"source":2,"destination":3,"rated_current":32.5,"phases":"Three"}]}"#
+            )
+        );
+
+        let reimported = ComponentGraph::from_json(&exported)?;
+        assert_eq!(reimported.to_json()?, exported);
+        assert_eq!(reimported.component(3)?.external_id(), Some("a1b2"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_metadata() -> Result<(), Error> {
+        let json = r#"{
+            "metadata": { "microgrid_id": 12, "name": "Depot" },
+            "components": [{ "id": 1, "category": "Grid" }, { "id": 2, "category": "Meter" }],
+            "connections": [{ "source": 1, "destination": 2 }]
+        }"#;
+
+        let graph = ComponentGraph::from_json(json)?;
+        assert_eq!(
+            graph.metadata(),
+            &SiteMetadata {
+                microgrid_id: Some(12),
+                name: Some("Depot".to_string()),
+                ..Default::default()
+            }
+        );
+
+        let exported = graph.to_json()?;
+        assert!(exported.starts_with(concat!(
+            r#"{"metadata":{"microgrid_id":12,"name":"Depot","location":null,"#,
+            r#""timezone":null,"extra":{}},"components":["#
+        )));
+        assert_eq!(
+            ComponentGraph::from_json(&exported)?.metadata(),
+            graph.metadata()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_errors() {
+        let Err(err) = ComponentGraph::from_json(r#"{ "components": [] }"#) else {
+            panic!("expected an error");
+        };
+        assert_eq!(err.kind(), ErrorKind::Serialization);
+
+        let Err(err) = ComponentGraph::from_json(
+            r#"{
+                "components": [
+                    { "id": 1, "category": "Grid" },
+                    { "id": 2, "category": "Battery" }
+                ],
+                "connections": [{ "source": 1, "destination": 2 }]
+            }"#,
+        ) else {
+            panic!("expected an error");
+        };
+        assert_eq!(err.kind(), ErrorKind::InvalidGraph);
+    }
+}
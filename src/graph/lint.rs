@@ -0,0 +1,195 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Advisory checks for a [`ComponentGraph`], that don't make the graph invalid.
+
+use crate::{component_category::CategoryPredicates, ComponentGraph, Edge, Error, MeterRole, Node};
+
+/// Best-practice checks.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns advisory findings about the graph.
+    ///
+    /// These are topologies that are valid, but are often a sign of missing or
+    /// wrong data in the site configuration:
+    ///   - PV and wind inverters that don't have a PV or wind meter as a
+    ///     predecessor,
+    ///   - meters that have no successors and are not grid meters.
+    pub fn lint(&self) -> Vec<Error> {
+        let mut findings = vec![];
+
+        for component in self.components() {
+            let cid = component.component_id();
+            let category = self.config.effective_category(component.category());
+            let result = if category.is_pv_inverter() {
+                self.lint_producer(component, MeterRole::Pv, "pv_meter")
+            } else if category.is_wind_inverter() {
+                self.lint_producer(component, MeterRole::Wind, "wind_meter")
+            } else if component.is_meter() {
+                self.lint_meter(component)
+            } else {
+                Ok(None)
+            };
+            match result {
+                Ok(Some(finding)) => findings.push(finding),
+                Ok(None) => {}
+                Err(err) => findings.push(
+                    Error::internal(format!("Unable to lint component {cid}: {err}"))
+                        .with_components([cid]),
+                ),
+            }
+        }
+
+        findings
+    }
+
+    /// Checks that the given producer has a predecessor meter with the given
+    /// role.
+    fn lint_producer(
+        &self,
+        inverter: &N,
+        role: MeterRole,
+        rule: &'static str,
+    ) -> Result<Option<Error>, Error> {
+        for predecessor in self.predecessors(inverter.component_id())? {
+            if self.meter_role(predecessor.component_id())? == Some(role) {
+                return Ok(None);
+            }
+        }
+        Ok(Some(
+            Error::lint(format!(
+                "{}:{} has no {role} meter as a predecessor.",
+                inverter.category(),
+                inverter.component_id()
+            ))
+            .with_components([inverter.component_id()])
+            .with_rule(rule),
+        ))
+    }
+
+    fn lint_meter(&self, meter: &N) -> Result<Option<Error>, Error> {
+        let cid = meter.component_id();
+        if self.meter_role(cid)? != Some(MeterRole::Dangling) {
+            return Ok(None);
+        }
+        Ok(Some(
+            Error::lint(format!(
+                "{}:{} has no successors and is not a grid meter.",
+                meter.category(),
+                cid
+            ))
+            .with_components([cid])
+            .with_rule("dangling_meter"),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentCategory, InverterType};
+
+    #[derive(Clone)]
+    struct TestComponent(u64, ComponentCategory);
+
+    impl Node for TestComponent {
+        fn component_id(&self) -> u64 {
+            self.0
+        }
+
+        fn category(&self) -> ComponentCategory {
+            self.1
+        }
+
+        fn is_supported(&self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Clone)]
+    struct TestConnection(u64, u64);
+
+    impl TestConnection {
+        fn new(source: u64, destination: u64) -> Self {
+            TestConnection(source, destination)
+        }
+    }
+
+    impl Edge for TestConnection {
+        fn source(&self) -> u64 {
+            self.0
+        }
+
+        fn destination(&self) -> u64 {
+            self.1
+        }
+    }
+
+    fn nodes_and_edges() -> (Vec<TestComponent>, Vec<TestConnection>) {
+        let components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Meter),
+            TestComponent(3, ComponentCategory::Meter),
+            TestComponent(4, ComponentCategory::Inverter(InverterType::Solar)),
+            TestComponent(5, ComponentCategory::Meter),
+            TestComponent(6, ComponentCategory::Inverter(InverterType::Battery)),
+            TestComponent(7, ComponentCategory::Inverter(InverterType::Battery)),
+            TestComponent(8, ComponentCategory::Battery),
+        ];
+        let connections = vec![
+            TestConnection::new(1, 2),
+            // PV chain
+            TestConnection::new(2, 3),
+            TestConnection::new(3, 4),
+            // Battery chain with a shared battery
+            TestConnection::new(2, 5),
+            TestConnection::new(5, 6),
+            TestConnection::new(5, 7),
+            TestConnection::new(6, 8),
+            TestConnection::new(7, 8),
+        ];
+
+        (components, connections)
+    }
+
+    #[test]
+    fn test_lint() -> Result<(), Error> {
+        let (mut components, mut connections) = nodes_and_edges();
+
+        let graph = ComponentGraph::try_new(components.clone(), connections.clone())?;
+        assert_eq!(graph.lint(), vec![]);
+
+        // A PV inverter directly under the grid meter.
+        components.push(TestComponent(
+            9,
+            ComponentCategory::Inverter(InverterType::Solar),
+        ));
+        connections.push(TestConnection::new(2, 9));
+
+        // A wind inverter directly under the grid meter.
+        components.push(TestComponent(
+            11,
+            ComponentCategory::Inverter(InverterType::Wind),
+        ));
+        connections.push(TestConnection::new(2, 11));
+
+        // A meter without successors.
+        components.push(TestComponent(10, ComponentCategory::Meter));
+        connections.push(TestConnection::new(2, 10));
+
+        let graph = ComponentGraph::try_new(components, connections)?;
+        assert_eq!(
+            graph.lint(),
+            vec![
+                Error::lint("SolarInverter:9 has no PV meter as a predecessor."),
+                Error::lint("WindInverter:11 has no Wind meter as a predecessor."),
+                Error::lint("Meter:10 has no successors and is not a grid meter."),
+            ]
+        );
+
+        Ok(())
+    }
+}
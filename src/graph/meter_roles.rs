@@ -3,7 +3,84 @@
 
 //! Methods for checking the roles of meters in a [`ComponentGraph`].
 
-use crate::{component_category::CategoryPredicates, ComponentGraph, Edge, Error, Node};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, RwLock};
+
+use crate::{
+    component_category::CategoryPredicates, ComponentCategory, ComponentGraph, Edge, Error,
+    InverterType, Node,
+};
+
+/// The role of a meter in a [`ComponentGraph`], based on its position in the
+/// graph and the categories of its successors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MeterRole {
+    /// A meter that measures the whole site, directly beneath the grid.
+    Grid,
+    /// A meter whose successors are all PV inverters.
+    Pv,
+    /// A meter whose successors are all wind inverters.
+    Wind,
+    /// A meter whose successors are all battery inverters.
+    Battery,
+    /// A meter whose successors are all EV chargers.
+    EvCharger,
+    /// A meter whose successors are all CHPs.
+    Chp,
+    /// A meter whose successors are not all of one of the above kinds, for
+    /// example a meter with both a CHP and a PV inverter as successors, or a
+    /// meter with other meters as successors.
+    Mixed,
+    /// A meter without successors other than sensors, that is not a grid
+    /// meter.
+    Dangling,
+}
+
+impl std::fmt::Display for MeterRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MeterRole::Grid => write!(f, "Grid"),
+            MeterRole::Pv => write!(f, "PV"),
+            MeterRole::Wind => write!(f, "Wind"),
+            MeterRole::Battery => write!(f, "Battery"),
+            MeterRole::EvCharger => write!(f, "EVCharger"),
+            MeterRole::Chp => write!(f, "CHP"),
+            MeterRole::Mixed => write!(f, "Mixed"),
+            MeterRole::Dangling => write!(f, "Dangling"),
+        }
+    }
+}
+
+/// A cache for the roles of all meters in a graph.
+///
+/// The cache can be cleared through a shared reference, so that
+/// [`ComponentGraph::revalidate`] can drop roles that were computed from
+/// outdated categories.
+#[derive(Default)]
+pub(super) struct MeterRoleCache(RwLock<Option<Arc<HashMap<u64, MeterRole>>>>);
+
+impl MeterRoleCache {
+    /// Returns the cached roles, if there are any.
+    pub(super) fn get(&self) -> Option<Arc<HashMap<u64, MeterRole>>> {
+        // The lock is only held for cloning or replacing the `Arc`, so a
+        // poisoned lock is still safe to use.
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Caches the given roles, and returns them.
+    pub(super) fn set(&self, roles: HashMap<u64, MeterRole>) -> Arc<HashMap<u64, MeterRole>> {
+        let roles = Arc::new(roles);
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = Some(roles.clone());
+        roles
+    }
+
+    /// Removes the cached roles, so that they are computed again when they
+    /// are next needed.
+    pub(super) fn clear(&self) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
 
 /// Meter role identification.
 impl<N, E> ComponentGraph<N, E>
@@ -11,10 +88,138 @@ where
     N: Node,
     E: Edge,
 {
+    /// Returns the role of the meter with the given `component_id`, or `None`
+    /// if the component is not a meter.
+    ///
+    /// A meter has exactly one role.  Grid meters are identified first, as
+    /// described in [`is_grid_meter`][Self::is_grid_meter], so a grid meter
+    /// that only has PV inverters as successors has the `Grid` role, not the
+    /// `Pv` role.
+    ///
+    /// The `is_*_meter` methods for the roles of single component kinds, like
+    /// [`is_pv_meter`][Self::is_pv_meter], only look at the successors of the
+    /// meter, so such a meter is both a grid meter and a PV meter according
+    /// to them.
+    ///
+    /// The roles of all meters are computed once, the first time this method
+    /// is called, and are served from a cache afterwards.  The cache is
+    /// cleared by [`revalidate`][Self::revalidate], so that roles follow
+    /// changes in the categories reported by the components.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn meter_role(&self, component_id: u64) -> Result<Option<MeterRole>, Error> {
+        if !self.component(component_id)?.is_meter() {
+            return Ok(None);
+        }
+        match self.meter_roles().get(&component_id) {
+            Some(role) => Ok(Some(*role)),
+            None => self.find_meter_role(component_id).map(Some),
+        }
+    }
+
+    /// Returns the roles of all meters, computing them on the first call.
+    pub(super) fn meter_roles(&self) -> Arc<HashMap<u64, MeterRole>> {
+        if let Some(roles) = self.meter_roles.get() {
+            return roles;
+        }
+        self.meter_roles.set(
+            self.components()
+                .filter(|n| n.is_meter())
+                .filter_map(|n| {
+                    let cid = n.component_id();
+                    Some((cid, self.find_meter_role(cid).ok()?))
+                })
+                .collect(),
+        )
+    }
+
+    /// Identifies the role of the given meter from the graph.
+    fn find_meter_role(&self, component_id: u64) -> Result<MeterRole, Error> {
+        if self.is_grid_meter(component_id)? {
+            return Ok(MeterRole::Grid);
+        }
+        self.find_successor_role(component_id)
+    }
+
+    /// Returns the role of the given component from its successors alone, or
+    /// `None` if the component is not a meter.
+    ///
+    /// Unlike [`meter_role`][Self::meter_role], this doesn't identify grid
+    /// meters, so a grid meter gets the role its successors would give it.
+    fn successor_role(&self, component_id: u64) -> Result<Option<MeterRole>, Error> {
+        match self.meter_role(component_id)? {
+            // Only grid meters have a cached role that doesn't come from
+            // their successors.
+            Some(MeterRole::Grid) => self.find_successor_role(component_id).map(Some),
+            role => Ok(role),
+        }
+    }
+
+    /// Identifies the role of the given meter from the categories of its
+    /// successors.
+    fn find_successor_role(&self, component_id: u64) -> Result<MeterRole, Error> {
+        let batteries_allowed = self.config.allow_batteries_under_meters;
+        let categories = self
+            .successors_through_transparent(component_id)?
+            .into_iter()
+            .filter(|n| !n.is_sensor())
+            .map(|n| self.config.effective_category(n.category()))
+            .collect::<Vec<_>>();
+        let all = |expected: &[ComponentCategory]| categories.iter().all(|c| expected.contains(c));
+
+        let role = if categories.is_empty() {
+            MeterRole::Dangling
+        } else if all(&[ComponentCategory::Inverter(InverterType::Solar)]) {
+            MeterRole::Pv
+        } else if all(&[ComponentCategory::Inverter(InverterType::Wind)]) {
+            MeterRole::Wind
+        } else if all(&[ComponentCategory::Inverter(InverterType::Battery)])
+            || (batteries_allowed
+                && all(&[
+                    ComponentCategory::Inverter(InverterType::Battery),
+                    ComponentCategory::Battery,
+                ]))
+        {
+            MeterRole::Battery
+        } else if categories
+            .iter()
+            .all(|c| matches!(c, ComponentCategory::EvCharger(_)))
+        {
+            MeterRole::EvCharger
+        } else if all(&[ComponentCategory::Chp]) {
+            MeterRole::Chp
+        } else {
+            MeterRole::Mixed
+        };
+        Ok(role)
+    }
+
+    /// Returns the successors of the given component, with transparent
+    /// components replaced by their own successors.
+    ///
+    /// Which components are transparent is decided by the
+    /// [`ComponentGraphConfig`][crate::ComponentGraphConfig] of the graph.
+    fn successors_through_transparent(&self, component_id: u64) -> Result<Vec<&N>, Error> {
+        let mut successors = vec![];
+        let mut visited = BTreeSet::new();
+        let mut stack = self.successors(component_id)?.collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node.component_id()) {
+                continue;
+            }
+            if self.config.is_transparent(node.category()) {
+                stack.extend(self.successors(node.component_id())?);
+            } else {
+                successors.push(node);
+            }
+        }
+        Ok(successors)
+    }
+
     /// Returns true if a node is a grid meter.
     ///
     /// A meter is identified as a grid meter if:
-    ///   - it is a successor of the grid component,
+    ///   - it is a successor of the grid component, or of the grid fuse,
     ///   - all its siblings are meters,
     ///   - if there are siblings, the successors of it and the successors of
     ///     its siblings are meters.
@@ -28,28 +233,36 @@ where
 
         let mut predecessors = self.predecessors(component_id)?;
 
-        // The meter must have a grid as a predecessor.
+        // The meter must have a grid, or the grid fuse, as a predecessor.
         let Some(grid) = predecessors.next() else {
             return Ok(false);
         };
 
         let has_multiple_predecessors = predecessors.next().is_some();
 
-        if !grid.is_grid() || has_multiple_predecessors {
+        let is_grid_fuse = || {
+            self.grid_fuse()
+                .is_some_and(|fuse| fuse.component_id() == grid.component_id())
+        };
+        if !(grid.is_grid() || is_grid_fuse()) || has_multiple_predecessors {
             return Ok(false);
         }
 
-        // All siblings must be meters.
+        // All siblings must be meters.  Sensors are ignored, because they
+        // don't take part in the power flow.
         let mut num_grid_successors = 0;
         let mut non_meter_successors = false;
         for grid_successor in self.successors(grid.component_id())? {
+            if grid_successor.is_sensor() {
+                continue;
+            }
             if grid_successor.is_meter() {
                 num_grid_successors += 1;
             } else {
                 return Ok(false);
             }
             let mut successors = self.successors(grid_successor.component_id())?;
-            if successors.any(|n| !n.is_meter()) {
+            if successors.any(|n| !n.is_meter() && !n.is_sensor()) {
                 non_meter_successors = true;
             }
         }
@@ -73,13 +286,16 @@ where
     ///   - it has atleast one successor,
     ///   - all its successors are PV inverters.
     pub fn is_pv_meter(&self, component_id: u64) -> Result<bool, Error> {
-        let mut has_successors = false;
-        Ok(self.component(component_id)?.is_meter()
-            && self.successors(component_id)?.all(|n| {
-                has_successors = true;
-                n.is_pv_inverter()
-            })
-            && has_successors)
+        Ok(self.successor_role(component_id)? == Some(MeterRole::Pv))
+    }
+
+    /// Returns true if the node is a wind meter.
+    ///
+    /// A meter is identified as a wind meter if:
+    ///   - it has atleast one successor,
+    ///   - all its successors are wind inverters.
+    pub fn is_wind_meter(&self, component_id: u64) -> Result<bool, Error> {
+        Ok(self.successor_role(component_id)? == Some(MeterRole::Wind))
     }
 
     /// Returns true if the node is a battery meter.
@@ -87,14 +303,12 @@ where
     /// A meter is identified as a battery meter if
     ///   - it has atleast one successor,
     ///   - all its successors are battery inverters.
+    ///
+    /// If [`allow_batteries_under_meters`][crate::ComponentGraphConfig::allow_batteries_under_meters]
+    /// is set, a meter whose successors are all batteries is also identified
+    /// as a battery meter.
     pub fn is_battery_meter(&self, component_id: u64) -> Result<bool, Error> {
-        let mut has_successors = false;
-        Ok(self.component(component_id)?.is_meter()
-            && self.successors(component_id)?.all(|n| {
-                has_successors = true;
-                n.is_battery_inverter()
-            })
-            && has_successors)
+        Ok(self.successor_role(component_id)? == Some(MeterRole::Battery))
     }
 
     /// Returns true if the node is an EV charger meter.
@@ -103,13 +317,7 @@ where
     ///   - it has atleast one successor,
     ///   - all its successors are EV chargers.
     pub fn is_ev_charger_meter(&self, component_id: u64) -> Result<bool, Error> {
-        let mut has_successors = false;
-        Ok(self.component(component_id)?.is_meter()
-            && self.successors(component_id)?.all(|n| {
-                has_successors = true;
-                n.is_ev_charger()
-            })
-            && has_successors)
+        Ok(self.successor_role(component_id)? == Some(MeterRole::EvCharger))
     }
 
     /// Returns true if the node is a CHP meter.
@@ -118,13 +326,19 @@ where
     ///   - has atleast one successor,
     ///   - all its successors are CHPs.
     pub fn is_chp_meter(&self, component_id: u64) -> Result<bool, Error> {
-        let mut has_successors = false;
-        Ok(self.component(component_id)?.is_meter()
-            && self.successors(component_id)?.all(|n| {
-                has_successors = true;
-                n.is_chp()
-            })
-            && has_successors)
+        Ok(self.successor_role(component_id)? == Some(MeterRole::Chp))
+    }
+
+    /// Returns true if the node is a mixed meter.
+    ///
+    /// A meter is identified as a mixed meter if
+    ///   - it has atleast one successor,
+    ///   - its successors are not all PV inverters, wind inverters, battery
+    ///     inverters, EV chargers or CHPs, for example when it has both CHPs and PV
+    ///     inverters as successors,
+    ///   - it is not a grid meter.
+    pub fn is_mixed_meter(&self, component_id: u64) -> Result<bool, Error> {
+        Ok(self.meter_role(component_id)? == Some(MeterRole::Mixed))
     }
 }
 
@@ -133,6 +347,8 @@ mod tests {
     use super::*;
     use crate::error::Error;
     use crate::ComponentCategory;
+    use crate::ComponentGraphConfig;
+    use crate::EvChargerType;
     use crate::InverterType;
 
     #[derive(Clone, Debug, PartialEq)]
@@ -144,7 +360,7 @@ mod tests {
         }
 
         fn category(&self) -> ComponentCategory {
-            self.1.clone()
+            self.1
         }
 
         fn is_supported(&self) -> bool {
@@ -262,7 +478,10 @@ mod tests {
         // Add an EV charger meter to the grid, then none of the meters
         // connected to the grid should be detected as grid meters.
         components.push(TestComponent(20, ComponentCategory::Meter));
-        components.push(TestComponent(21, ComponentCategory::EvCharger));
+        components.push(TestComponent(
+            21,
+            ComponentCategory::EvCharger(EvChargerType::Ac),
+        ));
         connections.push(TestConnection::new(1, 20));
         connections.push(TestConnection::new(20, 21));
 
@@ -380,6 +599,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_is_battery_meter_with_batteries_under_meters() -> Result<(), Error> {
+        let (mut components, mut connections) = nodes_and_edges();
+
+        components.push(TestComponent(19, ComponentCategory::Meter));
+        components.push(TestComponent(20, ComponentCategory::Battery));
+        components.push(TestComponent(21, ComponentCategory::Battery));
+        connections.push(TestConnection::new(2, 19));
+        connections.push(TestConnection::new(19, 20));
+        connections.push(TestConnection::new(19, 21));
+
+        let graph = ComponentGraph::try_new_with_config(
+            components,
+            connections,
+            ComponentGraphConfig {
+                allow_batteries_under_meters: true,
+                ..Default::default()
+            },
+        )?;
+
+        let battery_meters = graph
+            .components()
+            .filter(|n| graph.is_battery_meter(n.component_id()).unwrap_or(false))
+            .map(|n| n.component_id())
+            .collect::<Vec<_>>();
+        assert_eq!(battery_meters, vec![3, 6, 19]);
+
+        Ok(())
+    }
+
     #[test]
     fn test_is_chp_meter() -> Result<(), Error> {
         let (components, connections) = nodes_and_edges();
@@ -437,4 +686,339 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_is_mixed_meter() -> Result<(), Error> {
+        let (components, connections) = nodes_and_edges();
+        assert_meter_role(
+            components,
+            connections,
+            ComponentGraph::is_mixed_meter,
+            vec![14],
+        )?;
+
+        let (components, connections) = with_multiple_grid_meters();
+        assert_meter_role(
+            components,
+            connections,
+            ComponentGraph::is_mixed_meter,
+            vec![14],
+        )?;
+
+        let (components, connections) = without_grid_meters();
+        assert_meter_role(
+            components,
+            connections,
+            ComponentGraph::is_mixed_meter,
+            vec![2, 14],
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_meter_role() -> Result<(), Error> {
+        let (mut components, mut connections) = with_multiple_grid_meters();
+        components.push(TestComponent(30, ComponentCategory::Meter));
+        connections.push(TestConnection::new(2, 30));
+
+        let graph = ComponentGraph::try_new(components, connections)?;
+        let mut roles = vec![];
+        for comp in graph.components() {
+            if let Some(role) = graph.meter_role(comp.component_id())? {
+                roles.push((comp.component_id(), role));
+            }
+        }
+        assert_eq!(
+            roles,
+            vec![
+                (2, MeterRole::Grid),
+                (3, MeterRole::Battery),
+                (6, MeterRole::Battery),
+                (9, MeterRole::Pv),
+                (12, MeterRole::Chp),
+                (14, MeterRole::Mixed),
+                (19, MeterRole::Grid),
+                (20, MeterRole::Grid),
+                (21, MeterRole::Battery),
+                (24, MeterRole::Pv),
+                (30, MeterRole::Dangling),
+            ]
+        );
+
+        assert_eq!(graph.meter_role(1)?, None);
+        assert_eq!(graph.meter_role(5)?, None);
+        assert!(graph
+            .meter_role(100)
+            .is_err_and(|e| e == Error::component_not_found("Component with id 100 not found.")));
+
+        let (components, connections) = without_grid_meters();
+        let graph = ComponentGraph::try_new(components, connections)?;
+        assert_eq!(graph.meter_role(2)?, Some(MeterRole::Mixed));
+        assert_eq!(graph.meter_role(20)?, Some(MeterRole::EvCharger));
+
+        let (mut components, mut connections) = nodes_and_edges();
+        components.push(TestComponent(30, ComponentCategory::Meter));
+        components.push(TestComponent(
+            31,
+            ComponentCategory::Inverter(InverterType::Wind),
+        ));
+        components.push(TestComponent(
+            32,
+            ComponentCategory::Inverter(InverterType::Wind),
+        ));
+        connections.push(TestConnection::new(2, 30));
+        connections.push(TestConnection::new(30, 31));
+        connections.push(TestConnection::new(30, 32));
+        // A wind inverter in the mixed chain.
+        components.push(TestComponent(
+            33,
+            ComponentCategory::Inverter(InverterType::Wind),
+        ));
+        connections.push(TestConnection::new(14, 33));
+        let graph = ComponentGraph::try_new(components, connections)?;
+        assert_eq!(graph.meter_role(30)?, Some(MeterRole::Wind));
+        assert!(graph.is_wind_meter(30)?);
+        assert!(!graph.is_wind_meter(9)?);
+        assert_eq!(graph.meter_role(14)?, Some(MeterRole::Mixed));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_meter_under_grid() -> Result<(), Error> {
+        // A single meter under the grid is the grid meter, but the role
+        // checks for its successors still apply to it.
+        let cases = [
+            (
+                ComponentCategory::Inverter(InverterType::Solar),
+                ComponentGraph::is_pv_meter as fn(&_, u64) -> Result<bool, Error>,
+            ),
+            (
+                ComponentCategory::Inverter(InverterType::Battery),
+                ComponentGraph::is_battery_meter,
+            ),
+            (
+                ComponentCategory::EvCharger(EvChargerType::Ac),
+                ComponentGraph::is_ev_charger_meter,
+            ),
+            (ComponentCategory::Chp, ComponentGraph::is_chp_meter),
+        ];
+        for (category, is_role_meter) in cases {
+            let mut components = vec![
+                TestComponent(1, ComponentCategory::Grid),
+                TestComponent(2, ComponentCategory::Meter),
+                TestComponent(3, category),
+            ];
+            let mut connections = vec![TestConnection::new(1, 2), TestConnection::new(2, 3)];
+            if category.is_battery_inverter() {
+                components.push(TestComponent(4, ComponentCategory::Battery));
+                connections.push(TestConnection::new(3, 4));
+            }
+
+            let graph = ComponentGraph::try_new(components, connections)?;
+            assert_eq!(graph.meter_role(2)?, Some(MeterRole::Grid));
+            assert!(graph.is_grid_meter(2)?);
+            assert!(is_role_meter(&graph, 2)?, "{category}");
+            assert!(!graph.is_mixed_meter(2)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_meter_role_with_sensors() -> Result<(), Error> {
+        let (mut components, mut connections) = nodes_and_edges();
+        // A grid-frequency sensor next to the grid meter, an irradiance
+        // sensor under the PV meter, and a meter with only a sensor.
+        components.push(TestComponent(30, ComponentCategory::Sensor));
+        components.push(TestComponent(31, ComponentCategory::Sensor));
+        components.push(TestComponent(32, ComponentCategory::Meter));
+        components.push(TestComponent(33, ComponentCategory::Sensor));
+        connections.push(TestConnection::new(1, 30));
+        connections.push(TestConnection::new(9, 31));
+        connections.push(TestConnection::new(2, 32));
+        connections.push(TestConnection::new(32, 33));
+
+        let graph = ComponentGraph::try_new(components, connections)?;
+        assert_eq!(graph.meter_role(2)?, Some(MeterRole::Grid));
+        assert_eq!(graph.meter_role(9)?, Some(MeterRole::Pv));
+        assert_eq!(graph.meter_role(32)?, Some(MeterRole::Dangling));
+        assert_eq!(graph.meter_role(30)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_meter_role_cache() -> Result<(), Error> {
+        let (components, connections) = nodes_and_edges();
+        let graph = ComponentGraph::try_new(components, connections)?;
+        assert!(graph.meter_roles.get().is_none());
+
+        assert_eq!(graph.meter_role(9)?, Some(MeterRole::Pv));
+        let roles = graph.meter_roles.get().map(|roles| {
+            let mut roles = roles.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+            roles.sort_by_key(|(k, _)| *k);
+            roles
+        });
+        assert_eq!(
+            roles,
+            Some(vec![
+                (2, MeterRole::Grid),
+                (3, MeterRole::Battery),
+                (6, MeterRole::Battery),
+                (9, MeterRole::Pv),
+                (12, MeterRole::Chp),
+                (14, MeterRole::Mixed),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_meter_role_cache_after_revalidate() -> Result<(), Error> {
+        use std::cell::Cell;
+
+        /// A component whose category can be changed after the graph is
+        /// created.
+        struct MutableComponent(u64, Cell<ComponentCategory>);
+
+        impl Node for MutableComponent {
+            fn component_id(&self) -> u64 {
+                self.0
+            }
+
+            fn category(&self) -> ComponentCategory {
+                self.1.get()
+            }
+
+            fn is_supported(&self) -> bool {
+                true
+            }
+        }
+
+        let components = vec![
+            MutableComponent(1, Cell::new(ComponentCategory::Grid)),
+            MutableComponent(2, Cell::new(ComponentCategory::Meter)),
+            MutableComponent(3, Cell::new(ComponentCategory::Meter)),
+            MutableComponent(4, Cell::new(ComponentCategory::Chp)),
+        ];
+        let connections = vec![
+            TestConnection::new(1, 2),
+            TestConnection::new(2, 3),
+            TestConnection::new(3, 4),
+        ];
+        let graph = ComponentGraph::try_new(components, connections)?;
+        assert_eq!(graph.meter_role(3)?, Some(MeterRole::Chp));
+
+        graph.component(4)?.1.set(ComponentCategory::Electrolyzer);
+        // Roles are served from the cache until the graph is revalidated.
+        assert_eq!(graph.meter_role(3)?, Some(MeterRole::Chp));
+        assert!(graph.revalidate().is_valid());
+        assert_eq!(graph.meter_role(3)?, Some(MeterRole::Mixed));
+        assert!(!graph.is_chp_meter(3)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_meter_role_with_transparent_components() -> Result<(), Error> {
+        let (mut components, mut connections) = nodes_and_edges();
+
+        // A PV meter with a converter in front of its inverters, and a
+        // battery meter with a voltage transformer in front of its inverter.
+        components.push(TestComponent(19, ComponentCategory::Meter));
+        components.push(TestComponent(20, ComponentCategory::Converter));
+        components.push(TestComponent(
+            21,
+            ComponentCategory::Inverter(InverterType::Solar),
+        ));
+        components.push(TestComponent(
+            22,
+            ComponentCategory::Inverter(InverterType::Solar),
+        ));
+        components.push(TestComponent(23, ComponentCategory::Meter));
+        components.push(TestComponent(24, ComponentCategory::VoltageTransformer));
+        components.push(TestComponent(
+            25,
+            ComponentCategory::Inverter(InverterType::Battery),
+        ));
+        components.push(TestComponent(26, ComponentCategory::Battery));
+        connections.push(TestConnection::new(2, 19));
+        connections.push(TestConnection::new(19, 20));
+        connections.push(TestConnection::new(20, 21));
+        connections.push(TestConnection::new(20, 22));
+        connections.push(TestConnection::new(2, 23));
+        connections.push(TestConnection::new(23, 24));
+        connections.push(TestConnection::new(24, 25));
+        connections.push(TestConnection::new(25, 26));
+
+        assert!(
+            ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| e
+                == Error::invalid_graph(concat!(
+                    "SolarInverter:21 can only have predecessors with categories: ",
+                    "[Meter, Grid, Fuse]. Found Converter:20."
+                )))
+        );
+
+        let graph = ComponentGraph::try_new_with_config(
+            components,
+            connections,
+            ComponentGraphConfig {
+                transparent_converters: true,
+                transparent_voltage_transformers: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(graph.meter_role(19)?, Some(MeterRole::Pv));
+        assert_eq!(graph.meter_role(23)?, Some(MeterRole::Battery));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_meter_role_with_unspecified_inverters() -> Result<(), Error> {
+        let (mut components, mut connections) = nodes_and_edges();
+
+        components.push(TestComponent(19, ComponentCategory::Meter));
+        components.push(TestComponent(
+            20,
+            ComponentCategory::Inverter(InverterType::Unspecified),
+        ));
+        connections.push(TestConnection::new(2, 19));
+        connections.push(TestConnection::new(19, 20));
+
+        assert!(
+            ComponentGraph::try_new(components.clone(), connections.clone())
+                .is_err_and(|e| e
+                    == Error::invalid_component("InverterType not specified for inverter: 20"))
+        );
+
+        // Unspecified inverters are treated as battery inverters by default,
+        // which must have successors.
+        let config = ComponentGraphConfig {
+            allow_unspecified_inverters: true,
+            ..Default::default()
+        };
+        assert!(ComponentGraph::try_new_with_config(
+            components.clone(),
+            connections.clone(),
+            config.clone()
+        )
+        .is_err_and(|e| e
+            == Error::invalid_graph("UnspecifiedInverter:20 must have at least one successor.")));
+
+        let graph = ComponentGraph::try_new_with_config(
+            components,
+            connections,
+            ComponentGraphConfig {
+                unspecified_inverter_type: InverterType::Solar,
+                ..config
+            },
+        )?;
+        assert_eq!(graph.meter_role(19)?, Some(MeterRole::Pv));
+
+        Ok(())
+    }
 }
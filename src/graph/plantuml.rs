@@ -0,0 +1,120 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for rendering a [`ComponentGraph`] as a PlantUML diagram.
+
+use std::fmt::Write;
+
+use crate::{ComponentGraph, Edge, Node};
+
+/// PlantUML export.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns a PlantUML diagram of the graph.
+    ///
+    /// Each component is drawn as a rectangle labeled with its category and
+    /// ID, and the role of meters.  Connections are labeled with their rated
+    /// current and cable length, when known.  Components and connections are
+    /// written in the order of their IDs, so that the output is stable across
+    /// calls.
+    pub fn to_plantuml(&self) -> String {
+        let mut components = self.components().collect::<Vec<_>>();
+        components.sort_by_key(|c| c.component_id());
+        let mut connections = self.connections().collect::<Vec<_>>();
+        connections.sort_by_key(|c| (c.source(), c.destination()));
+
+        let mut out = String::from("@startuml\n");
+        // Infallible, writing to a `String` can't fail.
+        for component in components {
+            let cid = component.component_id();
+            let _ = write!(out, "rectangle \"{}:{cid}", component.category());
+            if let Ok(Some(role)) = self.meter_role(cid) {
+                let _ = write!(out, "\\n({role} meter)");
+            }
+            let _ = writeln!(out, "\" as c{cid}");
+        }
+        for connection in connections {
+            let _ = write!(
+                out,
+                "c{} --> c{}",
+                connection.source(),
+                connection.destination()
+            );
+            let label = [
+                connection.rated_current().map(|a| format!("{a} A")),
+                connection.cable_length().map(|m| format!("{m} m")),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+            if !label.is_empty() {
+                let _ = write!(out, " : {}", label.join(", "));
+            }
+            out.push('\n');
+        }
+        out.push_str("@enduml\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        builder::ComponentGraphBuilder, ComponentCategory, ComponentGraph, Error, InverterType,
+        SimpleComponent, SimpleConnection,
+    };
+
+    #[test]
+    fn test_to_plantuml() -> Result<(), Error> {
+        let mut builder = ComponentGraphBuilder::new();
+        let grid = builder.grid();
+        let grid_meter = builder.meter();
+        let pv_chain = builder.meter_pv_chain(1);
+        builder
+            .connect(grid, grid_meter)
+            .connect(grid_meter, pv_chain);
+        let graph = builder.build()?;
+
+        assert_eq!(
+            graph.to_plantuml(),
+            r#"@startuml
+rectangle "Grid:1" as c1
+rectangle "Meter:2\n(Grid meter)" as c2
+rectangle "Meter:3\n(PV meter)" as c3
+rectangle "SolarInverter:4" as c4
+c1 --> c2
+c2 --> c3
+c3 --> c4
+@enduml
+"#
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_plantuml_connection_ratings() -> Result<(), Error> {
+        let graph = ComponentGraph::try_new(
+            vec![
+                SimpleComponent::new(1, ComponentCategory::Grid),
+                SimpleComponent::new(2, ComponentCategory::Meter),
+                SimpleComponent::new(3, ComponentCategory::Inverter(InverterType::Solar)),
+            ],
+            vec![
+                SimpleConnection::new(1, 2).with_rated_current(63.0),
+                SimpleConnection::new(2, 3)
+                    .with_rated_current(32.5)
+                    .with_cable_length(120.0),
+            ],
+        )?;
+
+        assert!(graph
+            .to_plantuml()
+            .ends_with("c1 --> c2 : 63 A\nc2 --> c3 : 32.5 A, 120 m\n@enduml\n"));
+
+        Ok(())
+    }
+}
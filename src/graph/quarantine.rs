@@ -0,0 +1,268 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for creating a [`ComponentGraph`] from partly invalid data, by
+//! setting aside the components and connections that make it invalid.
+
+use crate::{ComponentGraph, ComponentGraphConfig, Edge, Error, Node};
+
+/// The rules of the checks that fail because of a successor of the component
+/// they are about, which is the last component of their errors.
+const SUCCESSOR_RULES: [&str; 3] = ["leaf", "successor_categories", "successor_not_categories"];
+
+/// The components and connections that were left out of a graph created with
+/// [`ComponentGraph::try_new_partial`], each with the reason it was left out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quarantine<N, E> {
+    /// The components that were left out, in the order they were left out.
+    pub components: Vec<(N, Error)>,
+    /// The connections that were left out, in the order they were left out.
+    ///
+    /// This includes the connections of the left out components.
+    pub connections: Vec<(E, Error)>,
+}
+
+impl<N, E> Default for Quarantine<N, E> {
+    fn default() -> Self {
+        Self {
+            components: vec![],
+            connections: vec![],
+        }
+    }
+}
+
+impl<N, E> Quarantine<N, E> {
+    /// Returns true if nothing was left out.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty() && self.connections.is_empty()
+    }
+}
+
+/// Partial graph creation.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node + Clone,
+    E: Edge + Clone,
+{
+    /// Creates a new [`ComponentGraph`] from the given components and
+    /// connections, leaving out the ones that would make it invalid instead
+    /// of failing.
+    ///
+    /// Problems are fixed one at a time, until the graph is valid:
+    ///   - for invalid connections, the connection is left out,
+    ///   - for components that can't be added, like duplicates, the
+    ///     component is left out,
+    ///   - for components with successors they can't have, the successor is
+    ///     left out, with its connections,
+    ///   - for other problems, the component that the failing check was
+    ///     about is left out, with its connections.
+    ///
+    /// The grid is never left out.
+    ///
+    /// Components that are cut off from the grid this way are left out as
+    /// well.  The left out components and connections are returned in a
+    /// [`Quarantine`], with the errors that caused them to be left out.
+    ///
+    /// Returns an error if no valid graph can be created, for example if
+    /// there is no grid component.
+    pub fn try_new_partial(
+        components: impl IntoIterator<Item = N>,
+        connections: impl IntoIterator<Item = E>,
+        config: ComponentGraphConfig,
+    ) -> Result<(Self, Quarantine<N, E>), Error> {
+        let mut components = components.into_iter().collect::<Vec<_>>();
+        let mut connections = connections.into_iter().collect::<Vec<_>>();
+        let mut quarantine = Quarantine::default();
+
+        loop {
+            let err = match Self::try_build(
+                components.iter().cloned(),
+                connections.iter().cloned(),
+                config.clone(),
+            ) {
+                // The components that can't be added to the graph are the
+                // later ones, like duplicates.
+                Err(err) => match (err.connection(), err.component_ids().last()) {
+                    (Some(_), _) => err,
+                    (None, Some(&cid)) => {
+                        let Some(position) =
+                            components.iter().rposition(|c| c.component_id() == cid)
+                        else {
+                            return Err(err);
+                        };
+                        Self::quarantine_component(
+                            &mut components,
+                            &mut connections,
+                            &mut quarantine,
+                            position,
+                            err,
+                        );
+                        continue;
+                    }
+                    (None, None) => return Err(err),
+                },
+                Ok(cg) => {
+                    let report = cg.revalidate();
+                    let Some(err) = report.errors().first() else {
+                        return Ok((cg, quarantine));
+                    };
+                    err.clone()
+                }
+            };
+
+            if let Some((source, destination)) = err.connection() {
+                let Some(position) = connections
+                    .iter()
+                    .position(|c| c.source() == source && c.destination() == destination)
+                else {
+                    return Err(err);
+                };
+                quarantine
+                    .connections
+                    .push((connections.remove(position), err));
+                continue;
+            }
+
+            let root_id = components
+                .iter()
+                .find(|c| c.category().is_grid())
+                .map(|c| c.component_id());
+            let mut candidates = err.component_ids().to_vec();
+            if err.rule().is_some_and(|rule| {
+                SUCCESSOR_RULES
+                    .iter()
+                    .any(|suffix| rule.rsplit("::").next() == Some(suffix))
+            }) {
+                candidates.reverse();
+            }
+            let Some(position) = candidates
+                .iter()
+                .find(|&&cid| Some(cid) != root_id)
+                .and_then(|&cid| components.iter().position(|c| c.component_id() == cid))
+            else {
+                return Err(err);
+            };
+            Self::quarantine_component(
+                &mut components,
+                &mut connections,
+                &mut quarantine,
+                position,
+                err,
+            );
+        }
+    }
+
+    /// Moves the component at the given `position` to the `quarantine`,
+    /// together with its connections, unless another component has the same
+    /// ID.
+    fn quarantine_component(
+        components: &mut Vec<N>,
+        connections: &mut Vec<E>,
+        quarantine: &mut Quarantine<N, E>,
+        position: usize,
+        err: Error,
+    ) {
+        let component = components.remove(position);
+        let cid = component.component_id();
+        if components.iter().any(|c| c.component_id() == cid) {
+            quarantine.components.push((component, err));
+            return;
+        }
+        let (removed, kept) = std::mem::take(connections)
+            .into_iter()
+            .partition::<Vec<_>, _>(|c| c.source() == cid || c.destination() == cid);
+        *connections = kept;
+        quarantine
+            .connections
+            .extend(removed.into_iter().map(|c| (c, err.clone())));
+        quarantine.components.push((component, err));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentCategory, InverterType, SimpleComponent, SimpleConnection};
+
+    #[test]
+    fn test_try_new_partial() -> Result<(), Error> {
+        let components = vec![
+            SimpleComponent::new(1, ComponentCategory::Grid),
+            SimpleComponent::new(2, ComponentCategory::Meter),
+            SimpleComponent::new(3, ComponentCategory::Inverter(InverterType::Battery)),
+            SimpleComponent::new(4, ComponentCategory::Battery),
+            SimpleComponent::new(5, ComponentCategory::Meter),
+            SimpleComponent::new(6, ComponentCategory::Inverter(InverterType::Solar)),
+            // A duplicate ID.
+            SimpleComponent::new(6, ComponentCategory::Chp),
+            // A battery without an inverter.
+            SimpleComponent::new(7, ComponentCategory::Battery),
+        ];
+        let connections = vec![
+            SimpleConnection::new(1, 2),
+            SimpleConnection::new(2, 3),
+            SimpleConnection::new(3, 4),
+            SimpleConnection::new(2, 5),
+            SimpleConnection::new(5, 6),
+            SimpleConnection::new(5, 7),
+            // A connection to a missing component.
+            SimpleConnection::new(5, 8),
+        ];
+
+        assert!(ComponentGraph::try_new(components.clone(), connections.clone()).is_err());
+
+        let (graph, quarantine) =
+            ComponentGraph::try_new_partial(components, connections, Default::default())?;
+
+        assert_eq!(
+            graph.components().map(|c| c.id).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(
+            graph.component(6)?.category,
+            ComponentCategory::Inverter(InverterType::Solar)
+        );
+        assert_eq!(
+            quarantine
+                .components
+                .iter()
+                .map(|(c, e)| (c.id, c.category, e.description()))
+                .collect::<Vec<_>>(),
+            vec![
+                (6, ComponentCategory::Chp, "Duplicate component ID found: 6"),
+                (
+                    7,
+                    ComponentCategory::Battery,
+                    "Meter:5 can't have successors with categories [Battery]. Found Battery:7."
+                ),
+            ]
+        );
+        assert_eq!(
+            quarantine
+                .connections
+                .iter()
+                .map(|(c, _)| (c.source, c.destination))
+                .collect::<Vec<_>>(),
+            vec![(5, 8), (5, 7)]
+        );
+
+        let (_, quarantine) = ComponentGraph::try_new_partial(
+            graph.components().cloned(),
+            graph.connections().cloned(),
+            Default::default(),
+        )?;
+        assert!(quarantine.is_empty());
+
+        assert_eq!(
+            ComponentGraph::<SimpleComponent, SimpleConnection>::try_new_partial(
+                vec![SimpleComponent::new(1, ComponentCategory::Meter)],
+                vec![],
+                Default::default(),
+            )
+            .err(),
+            Some(Error::invalid_graph("No grid component found."))
+        );
+
+        Ok(())
+    }
+}
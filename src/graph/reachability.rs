@@ -0,0 +1,258 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for checking whether components of a [`ComponentGraph`] are
+//! upstream of each other, or of components matching a predicate.
+
+use std::collections::HashSet;
+
+use petgraph::{algo::toposort, graph::NodeIndex};
+
+use crate::{iterators::Neighbors, ComponentGraph, Edge, Error, Node};
+
+/// Reachability queries.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns true if the component with the `downstream` ID can be reached
+    /// from the component with the `upstream` ID, by following connections.
+    ///
+    /// A component is not upstream of itself.  Each call walks the graph
+    /// below `upstream`, so for many queries, precompute a
+    /// [`Reachability`] with [`reachability`][Self::reachability] instead.
+    ///
+    /// Returns an error if either of the given component IDs doesn't exist.
+    pub fn is_upstream_of(&self, upstream: u64, downstream: u64) -> Result<bool, Error> {
+        self.component(downstream)?;
+        self.has_successor_matching(upstream, |n| n.component_id() == downstream)
+    }
+
+    /// Returns true if any component below the component with the given
+    /// `component_id` matches the given `predicate`.
+    ///
+    /// All components that can be reached by following connections are
+    /// checked, not only the direct successors.  For example, this checks if
+    /// there is a PV inverter anywhere below a meter:
+    ///
+    /// ```
+    /// # use component_graph::{builder::ComponentGraphBuilder, *};
+    /// # let mut builder = ComponentGraphBuilder::new();
+    /// # let grid = builder.grid();
+    /// # let meter = builder.meter();
+    /// # let pv_chain = builder.meter_pv_chain(1);
+    /// # builder.connect(grid, meter).connect(meter, pv_chain);
+    /// # let graph = builder.build()?;
+    /// let has_pv = graph.has_successor_matching(2, |n| {
+    ///     n.category() == ComponentCategory::Inverter(InverterType::Solar)
+    /// })?;
+    /// assert!(has_pv);
+    /// # Ok::<(), Error>(())
+    /// ```
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn has_successor_matching(
+        &self,
+        component_id: u64,
+        predicate: impl Fn(&N) -> bool,
+    ) -> Result<bool, Error> {
+        self.has_neighbor_matching(component_id, Self::successors, predicate)
+    }
+
+    /// Returns true if any component above the component with the given
+    /// `component_id` matches the given `predicate`.
+    ///
+    /// Like [`has_successor_matching`][Self::has_successor_matching], all
+    /// components up to the grid are checked, not only the direct
+    /// predecessors.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn has_predecessor_matching(
+        &self,
+        component_id: u64,
+        predicate: impl Fn(&N) -> bool,
+    ) -> Result<bool, Error> {
+        self.has_neighbor_matching(component_id, Self::predecessors, predicate)
+    }
+
+    /// Walks the graph from the given component in the direction given by
+    /// `neighbors`, and returns true when it finds a component that matches
+    /// the given `predicate`.
+    fn has_neighbor_matching<'a>(
+        &'a self,
+        component_id: u64,
+        neighbors: impl Fn(&'a Self, u64) -> Result<Neighbors<'a, N>, Error>,
+        predicate: impl Fn(&N) -> bool,
+    ) -> Result<bool, Error> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![component_id];
+        while let Some(id) = stack.pop() {
+            for neighbor in neighbors(self, id)? {
+                if predicate(neighbor) {
+                    return Ok(true);
+                }
+                if visited.insert(neighbor.component_id()) {
+                    stack.push(neighbor.component_id());
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Returns a [`Reachability`] with the components below each component
+    /// precomputed, for answering many
+    /// [`is_upstream_of`][Reachability::is_upstream_of] queries quickly.
+    ///
+    /// This takes `n² / 8` bytes of memory, for a graph of `n` components.
+    pub fn reachability(&self) -> Result<Reachability<'_, N, E>, Error> {
+        let order = toposort(&self.graph, None)
+            .map_err(|_| Error::internal("Unable to sort the components of a cyclic graph."))?;
+        let words = self.graph.node_count().div_ceil(64);
+        let mut below = vec![vec![0u64; words]; self.graph.node_count()];
+        for index in order.into_iter().rev() {
+            let mut bits = vec![0u64; words];
+            for successor in &self.adjacency[index.index()].successors {
+                let s = successor.index();
+                bits[s / 64] |= 1 << (s % 64);
+                for (bit, successor_bit) in bits.iter_mut().zip(&below[s]) {
+                    *bit |= successor_bit;
+                }
+            }
+            below[index.index()] = bits;
+        }
+        Ok(Reachability { cg: self, below })
+    }
+}
+
+/// The components below each component of a [`ComponentGraph`], returned by
+/// [`ComponentGraph::reachability`].
+pub struct Reachability<'a, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    cg: &'a ComponentGraph<N, E>,
+    /// For each node, a bitset of the nodes below it, addressed by the index
+    /// of their `NodeIndex`.
+    below: Vec<Vec<u64>>,
+}
+
+impl<N, E> Reachability<'_, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns true if the component with the `downstream` ID can be reached
+    /// from the component with the `upstream` ID, like
+    /// [`ComponentGraph::is_upstream_of`], but in constant time.
+    ///
+    /// Returns an error if either of the given component IDs doesn't exist.
+    pub fn is_upstream_of(&self, upstream: u64, downstream: u64) -> Result<bool, Error> {
+        let upstream = self.index(upstream)?.index();
+        let downstream = self.index(downstream)?.index();
+        Ok(self.below[upstream][downstream / 64] & (1 << (downstream % 64)) != 0)
+    }
+
+    fn index(&self, component_id: u64) -> Result<NodeIndex, Error> {
+        self.cg
+            .node_indices
+            .get(&component_id)
+            .copied()
+            .ok_or_else(|| {
+                Error::component_not_found(format!("Component with id {} not found.", component_id))
+                    .with_components([component_id])
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builder::ComponentGraphBuilder, component_category::CategoryPredicates};
+
+    #[test]
+    fn test_is_upstream_of() -> Result<(), Error> {
+        let mut builder = ComponentGraphBuilder::new();
+        let grid = builder.grid();
+        let grid_meter = builder.meter();
+        let bat_chain = builder.meter_bat_chain(2, 1);
+        let pv_chain = builder.meter_pv_chain(1);
+        builder
+            .connect(grid, grid_meter)
+            .connect(grid_meter, bat_chain)
+            .connect(grid_meter, pv_chain);
+        let graph = builder.build()?;
+        // Grid:1 -> Meter:2 -> Meter:3 -> BatteryInverter:4,5 -> Battery:6
+        //                   -> Meter:7 -> SolarInverter:8
+        let reachability = graph.reachability()?;
+
+        let ids = 1..=8;
+        for upstream in ids.clone() {
+            for downstream in ids.clone() {
+                assert_eq!(
+                    reachability.is_upstream_of(upstream, downstream)?,
+                    graph.is_upstream_of(upstream, downstream)?,
+                    "{upstream} -> {downstream}"
+                );
+            }
+        }
+
+        assert!(graph.is_upstream_of(1, 6)?);
+        assert!(graph.is_upstream_of(3, 6)?);
+        assert!(graph.is_upstream_of(5, 6)?);
+        assert!(!graph.is_upstream_of(7, 6)?);
+        assert!(!graph.is_upstream_of(6, 3)?);
+        assert!(!graph.is_upstream_of(4, 5)?);
+        assert!(!graph.is_upstream_of(2, 2)?);
+        assert!(reachability.is_upstream_of(2, 8)?);
+        assert!(!reachability.is_upstream_of(8, 2)?);
+
+        for result in [
+            graph.is_upstream_of(1, 9),
+            graph.is_upstream_of(9, 1),
+            reachability.is_upstream_of(1, 9),
+            reachability.is_upstream_of(9, 1),
+        ] {
+            assert_eq!(
+                result,
+                Err(Error::component_not_found("Component with id 9 not found."))
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_has_neighbor_matching() -> Result<(), Error> {
+        let mut builder = ComponentGraphBuilder::new();
+        let grid = builder.grid();
+        let grid_meter = builder.meter();
+        let bat_chain = builder.meter_bat_chain(1, 1);
+        let pv_chain = builder.meter_pv_chain(1);
+        builder
+            .connect(grid, grid_meter)
+            .connect(grid_meter, bat_chain)
+            .connect(grid_meter, pv_chain);
+        let graph = builder.build()?;
+        // Grid:1 -> Meter:2 -> Meter:3 -> BatteryInverter:4 -> Battery:5
+        //                   -> Meter:6 -> SolarInverter:7
+
+        assert!(graph.has_successor_matching(2, |n| n.is_battery())?);
+        assert!(graph.has_successor_matching(3, |n| n.is_battery())?);
+        assert!(!graph.has_successor_matching(6, |n| n.is_battery())?);
+        assert!(!graph.has_successor_matching(5, |_| true)?);
+
+        assert!(graph.has_predecessor_matching(5, |n| n.is_grid())?);
+        assert!(graph.has_predecessor_matching(7, |n| n.component_id() == 6)?);
+        assert!(!graph.has_predecessor_matching(7, |n| n.component_id() == 3)?);
+        assert!(!graph.has_predecessor_matching(1, |_| true)?);
+
+        assert_eq!(
+            graph.has_successor_matching(9, |_| true),
+            Err(Error::component_not_found("Component with id 9 not found."))
+        );
+
+        Ok(())
+    }
+}
@@ -3,8 +3,10 @@
 
 //! Methods for retrieving components and connections from a [`ComponentGraph`].
 
-use crate::iterators::{Components, Connections, Neighbors};
-use crate::{ComponentGraph, Edge, Error, Node};
+use std::collections::{HashSet, VecDeque};
+
+use crate::iterators::{Components, Connections, ConnectionsWithNodes, Descendants, Neighbors};
+use crate::{ComponentGraph, ComponentGraphConfig, Edge, Error, Node, Phase, SiteMetadata};
 
 /// `Component` and `Connection` retrieval.
 impl<N, E> ComponentGraph<N, E>
@@ -12,6 +14,33 @@ where
     N: Node,
     E: Edge,
 {
+    /// Returns the configuration the graph was created with.
+    pub fn config(&self) -> &ComponentGraphConfig {
+        &self.config
+    }
+
+    /// Returns the site metadata attached to the graph.
+    pub fn metadata(&self) -> &SiteMetadata {
+        &self.metadata
+    }
+
+    /// Returns the component ID of the root of the graph, the grid.
+    pub(crate) fn root_id(&self) -> u64 {
+        self.root_id
+    }
+
+    /// Returns the fuse of the grid connection, if the graph has one.
+    ///
+    /// The grid fuse is the fuse directly under the grid, of which a valid
+    /// graph has at most one, and its
+    /// [`rated_current`][Node::rated_current] is the limit of the grid
+    /// connection.
+    pub fn grid_fuse(&self) -> Option<&N> {
+        self.successors(self.root_id)
+            .ok()?
+            .find(|n| n.category().is_fuse())
+    }
+
     /// Returns the component with the given `component_id`, if it exists.
     pub fn component(&self, component_id: u64) -> Result<&N, Error> {
         self.node_indices
@@ -19,58 +48,179 @@ where
             .map(|i| &self.graph[*i])
             .ok_or_else(|| {
                 Error::component_not_found(format!("Component with id {} not found.", component_id))
+                    .with_components([component_id])
             })
     }
 
-    /// Returns an iterator over the components in the graph.
-    pub fn components(&self) -> Components<N> {
+    /// Returns the component with the given `external_id`, as returned by
+    /// [`Node::external_id`], if it exists.
+    pub fn component_by_external_id(&self, external_id: &str) -> Result<&N, Error> {
+        self.external_indices
+            .get(external_id)
+            .map(|i| &self.graph[*i])
+            .ok_or_else(|| {
+                Error::component_not_found(format!(
+                    "Component with external id {} not found.",
+                    external_id
+                ))
+            })
+    }
+
+    /// Returns an iterator over the components in the graph, in the order
+    /// they were given when the graph was created.
+    pub fn components(&self) -> Components<'_, N> {
         Components {
             iter: self.graph.raw_nodes().iter(),
         }
     }
 
-    /// Returns an iterator over the connections in the graph.
-    pub fn connections(&self) -> Connections<N, E> {
+    /// Returns an iterator over the connections in the graph, in the order
+    /// they were given when the graph was created.
+    ///
+    /// Duplicate connections are only returned once.
+    pub fn connections(&self) -> Connections<'_, N, E> {
         Connections {
             cg: self,
             iter: self.graph.raw_edges().iter(),
         }
     }
 
+    /// Returns an iterator over the connections in the graph, like
+    /// [`connections`][Self::connections], together with their source and
+    /// destination components, as `(source, connection, destination)`.
+    pub fn connections_with_nodes(&self) -> ConnectionsWithNodes<'_, N, E> {
+        ConnectionsWithNodes {
+            cg: self,
+            iter: self.graph.raw_edges().iter(),
+        }
+    }
+
     /// Returns an iterator over the *predecessors* of the component with the
-    /// given `component_id`.
+    /// given `component_id`, in the order of their component IDs.
     ///
     /// Returns an error if the given `component_id` does not exist.
-    pub fn predecessors(&self, component_id: u64) -> Result<Neighbors<N>, Error> {
+    pub fn predecessors(&self, component_id: u64) -> Result<Neighbors<'_, N>, Error> {
         self.node_indices
             .get(&component_id)
             .map(|&index| Neighbors {
                 graph: &self.graph,
-                iter: self
-                    .graph
-                    .neighbors_directed(index, petgraph::Direction::Incoming),
+                iter: self.adjacency[index.index()].predecessors.iter(),
             })
             .ok_or_else(|| {
                 Error::component_not_found(format!("Component with id {} not found.", component_id))
+                    .with_components([component_id])
             })
     }
 
     /// Returns an iterator over the *successors* of the component with the
-    /// given `component_id`.
+    /// given `component_id`, in the order of their component IDs.
     ///
     /// Returns an error if the given `component_id` does not exist.
-    pub fn successors(&self, component_id: u64) -> Result<Neighbors<N>, Error> {
+    pub fn successors(&self, component_id: u64) -> Result<Neighbors<'_, N>, Error> {
         self.node_indices
             .get(&component_id)
             .map(|&index| Neighbors {
                 graph: &self.graph,
-                iter: self
-                    .graph
-                    .neighbors_directed(index, petgraph::Direction::Outgoing),
+                iter: self.adjacency[index.index()].successors.iter(),
             })
             .ok_or_else(|| {
                 Error::component_not_found(format!("Component with id {} not found.", component_id))
+                    .with_components([component_id])
+            })
+    }
+
+    /// Returns an iterator over the *successors* of the component with the
+    /// given `component_id` whose connections carry the given `phase`, in the
+    /// order of their component IDs.
+    ///
+    /// Connections with unknown [`phases`][Edge::phases] are treated as
+    /// three-phase, so their successors are always included.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn successors_on_phase(
+        &self,
+        component_id: u64,
+        phase: Phase,
+    ) -> Result<impl Iterator<Item = &N> + '_, Error> {
+        let &index = self.node_indices.get(&component_id).ok_or_else(|| {
+            Error::component_not_found(format!("Component with id {} not found.", component_id))
+                .with_components([component_id])
+        })?;
+        Ok(self.adjacency[index.index()]
+            .successors
+            .iter()
+            .filter(move |&&successor| {
+                self.edges[&(index, successor)]
+                    .phases()
+                    .is_none_or(|phases| phases.contains(phase))
             })
+            .map(|&successor| &self.graph[successor]))
+    }
+
+    /// Returns true if the component with the ID `a` is a direct successor of
+    /// the component with the ID `b`, i.e. there is a connection from `b` to
+    /// `a`.
+    ///
+    /// Returns an error if either of the given component IDs doesn't exist.
+    pub fn is_direct_successor(&self, a: u64, b: u64) -> Result<bool, Error> {
+        self.component(a)?;
+        Ok(self.successors(b)?.any(|n| n.component_id() == a))
+    }
+
+    /// Returns true if the component with the ID `a` is a direct predecessor
+    /// of the component with the ID `b`, i.e. there is a connection from `a`
+    /// to `b`.
+    ///
+    /// Returns an error if either of the given component IDs doesn't exist.
+    pub fn is_direct_predecessor(&self, a: u64, b: u64) -> Result<bool, Error> {
+        self.is_direct_successor(b, a)
+    }
+
+    /// Returns the number of predecessors and successors of the component
+    /// with the given `component_id`, as `(in, out)`.
+    ///
+    /// Duplicate connections are only counted once.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn degree(&self, component_id: u64) -> Result<(usize, usize), Error> {
+        Ok((
+            self.predecessors(component_id)?.len(),
+            self.successors(component_id)?.len(),
+        ))
+    }
+
+    /// Returns a breadth-first iterator over the components below the
+    /// component with the given `component_id`.
+    ///
+    /// With a `max_depth`, only components up to that many connections away
+    /// are returned, so `Some(1)` gives the direct successors.  Components
+    /// reachable over multiple paths are only returned once, at their
+    /// shortest distance.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn descendants(
+        &self,
+        component_id: u64,
+        max_depth: Option<usize>,
+    ) -> Result<Descendants<'_, N>, Error> {
+        let index = self.node_indices.get(&component_id).ok_or_else(|| {
+            Error::component_not_found(format!("Component with id {} not found.", component_id))
+                .with_components([component_id])
+        })?;
+        let mut descendants = Descendants {
+            graph: &self.graph,
+            adjacency: &self.adjacency,
+            queue: VecDeque::new(),
+            visited: HashSet::new(),
+            max_depth,
+        };
+        if max_depth != Some(0) {
+            for &successor in &self.adjacency[index.index()].successors {
+                descendants.visited.insert(successor);
+                descendants.queue.push_back((successor, 1));
+            }
+        }
+        Ok(descendants)
     }
 }
 
@@ -90,7 +240,7 @@ mod tests {
         }
 
         fn category(&self) -> ComponentCategory {
-            self.1.clone()
+            self.1
         }
 
         fn is_supported(&self) -> bool {
@@ -168,6 +318,13 @@ mod tests {
         let graph = ComponentGraph::try_new(components.clone(), connections.clone())?;
 
         assert!(graph.components().eq(&components));
+        assert_eq!(graph.components().len(), components.len());
+        assert!(graph.components().rev().eq(components.iter().rev()));
+
+        let mut iter = graph.components();
+        iter.by_ref().for_each(drop);
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
         assert!(graph
             .components()
             .filter(|x| x.category() == ComponentCategory::Battery)
@@ -191,6 +348,76 @@ mod tests {
             .filter(|x| x.source() == 2)
             .eq(&[TestConnection::new(2, 3), TestConnection::new(2, 6)]));
 
+        assert_eq!(graph.connections().len(), connections.len());
+        assert!(graph
+            .connections_with_nodes()
+            .map(|(source, connection, destination)| (source.0, connection, destination.0))
+            .eq(connections.iter().map(|c| (c.0, c, c.1))));
+        assert!(graph.connections_with_nodes().next_back().is_some_and(
+            |(source, _, destination)| {
+                source == &TestComponent(2, ComponentCategory::Meter)
+                    && destination == &TestComponent(6, ComponentCategory::Meter)
+            }
+        ));
+        assert!(graph.connections().rev().eq(connections.iter().rev()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_direct_neighbors() -> Result<(), Error> {
+        let (components, connections) = nodes_and_edges();
+        let graph = ComponentGraph::try_new(components, connections)?;
+
+        assert!(graph.is_direct_successor(2, 1)?);
+        assert!(graph.is_direct_successor(6, 2)?);
+        assert!(!graph.is_direct_successor(1, 2)?);
+        assert!(!graph.is_direct_successor(3, 1)?);
+        assert!(graph.is_direct_predecessor(1, 2)?);
+        assert!(!graph.is_direct_predecessor(2, 1)?);
+        assert!(!graph.is_direct_predecessor(2, 4)?);
+
+        assert_eq!(graph.degree(1)?, (0, 1));
+        assert_eq!(graph.degree(2)?, (1, 2));
+        assert_eq!(graph.degree(5)?, (1, 0));
+
+        for result in [
+            graph.is_direct_successor(1, 32),
+            graph.is_direct_successor(32, 1),
+            graph.is_direct_predecessor(32, 1),
+            graph.degree(32).map(|_| true),
+        ] {
+            assert_eq!(
+                result,
+                Err(Error::component_not_found(
+                    "Component with id 32 not found."
+                ))
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_descendants() -> Result<(), Error> {
+        let (components, connections) = nodes_and_edges();
+        let graph = ComponentGraph::try_new(components.clone(), connections.clone())?;
+        let descendants = |id, max_depth| -> Result<Vec<u64>, Error> {
+            Ok(graph
+                .descendants(id, max_depth)?
+                .map(|c| c.component_id())
+                .collect())
+        };
+
+        assert_eq!(descendants(1, None)?, vec![2, 3, 6, 4, 7, 5, 8]);
+        assert_eq!(descendants(1, Some(2))?, vec![2, 3, 6]);
+        assert_eq!(descendants(2, Some(1))?, vec![3, 6]);
+        assert!(descendants(2, Some(0))?.is_empty());
+        assert!(descendants(5, None)?.is_empty());
+        assert!(graph
+            .descendants(32, None)
+            .is_err_and(|e| e == Error::component_not_found("Component with id 32 not found.")));
+
         Ok(())
     }
 
@@ -211,13 +438,29 @@ mod tests {
 
         assert!(graph.successors(2).is_ok_and(|x| {
             x.eq(&[
-                TestComponent(6, ComponentCategory::Meter),
                 TestComponent(3, ComponentCategory::Meter),
+                TestComponent(6, ComponentCategory::Meter),
             ])
         }));
 
         assert!(graph.successors(5).is_ok_and(|x| x.eq(&[])));
 
+        // The order doesn't depend on the order of the connections.
+        let reversed = ComponentGraph::try_new(components, connections.into_iter().rev())?;
+        assert!(reversed.successors(2).is_ok_and(|x| {
+            x.eq(&[
+                TestComponent(3, ComponentCategory::Meter),
+                TestComponent(6, ComponentCategory::Meter),
+            ])
+        }));
+        assert!(graph.successors(2).is_ok_and(|x| x.len() == 2));
+        assert!(graph.successors(2).is_ok_and(|x| {
+            x.rev().eq(&[
+                TestComponent(6, ComponentCategory::Meter),
+                TestComponent(3, ComponentCategory::Meter),
+            ])
+        }));
+
         assert!(graph
             .predecessors(32)
             .is_err_and(|e| e == Error::component_not_found("Component with id 32 not found.")));
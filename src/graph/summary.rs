@@ -0,0 +1,124 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for counting the components of a [`ComponentGraph`] by category
+//! and role.
+
+use std::collections::HashMap;
+
+use crate::{ComponentCategory, ComponentGraph, Edge, InverterType, MeterRole, Node};
+
+/// The number of components of each category, inverter type and meter role
+/// in a [`ComponentGraph`], returned by [`ComponentGraph::summary`].
+///
+/// Summaries of multiple graphs can be combined with
+/// [`merge`][GraphSummary::merge], for inventories across sites.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphSummary {
+    /// The number of components of each category.
+    pub categories: HashMap<ComponentCategory, usize>,
+    /// The number of inverters of each type.
+    pub inverter_types: HashMap<InverterType, usize>,
+    /// The number of meters with each role.
+    pub meter_roles: HashMap<MeterRole, usize>,
+}
+
+impl GraphSummary {
+    /// Adds the counts of the `other` summary to this one.
+    pub fn merge(&mut self, other: &GraphSummary) {
+        fn add<K: Copy + Eq + std::hash::Hash>(
+            into: &mut HashMap<K, usize>,
+            from: &HashMap<K, usize>,
+        ) {
+            for (&key, count) in from {
+                *into.entry(key).or_default() += count;
+            }
+        }
+        add(&mut self.categories, &other.categories);
+        add(&mut self.inverter_types, &other.inverter_types);
+        add(&mut self.meter_roles, &other.meter_roles);
+    }
+}
+
+/// Inventory summaries.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns the number of components of each category, inverter type and
+    /// meter role in the graph.
+    ///
+    /// Categories, types and roles without components are left out.
+    pub fn summary(&self) -> GraphSummary {
+        let mut summary = GraphSummary::default();
+        for component in self.components() {
+            let category = component.category();
+            *summary.categories.entry(category).or_default() += 1;
+            if let ComponentCategory::Inverter(inverter_type) = category {
+                *summary.inverter_types.entry(inverter_type).or_default() += 1;
+            }
+        }
+        for &role in self.meter_roles().values() {
+            *summary.meter_roles.entry(role).or_default() += 1;
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builder::ComponentGraphBuilder, Error, EvChargerType};
+
+    #[test]
+    fn test_summary() -> Result<(), Error> {
+        let mut builder = ComponentGraphBuilder::new();
+        let grid = builder.grid();
+        let grid_meter = builder.meter();
+        let bat_chain = builder.meter_bat_chain(2, 1);
+        let pv_chain = builder.meter_pv_chain(3);
+        let ev_chain = builder.meter_ev_charger_chain(1);
+        builder
+            .connect(grid, grid_meter)
+            .connect(grid_meter, bat_chain)
+            .connect(grid_meter, pv_chain)
+            .connect(grid_meter, ev_chain);
+        let graph = builder.build()?;
+
+        let summary = graph.summary();
+        assert_eq!(
+            summary.categories,
+            HashMap::from([
+                (ComponentCategory::Grid, 1),
+                (ComponentCategory::Meter, 4),
+                (ComponentCategory::Inverter(InverterType::Battery), 2),
+                (ComponentCategory::Battery, 1),
+                (ComponentCategory::Inverter(InverterType::Solar), 3),
+                (ComponentCategory::EvCharger(EvChargerType::Ac), 1),
+            ])
+        );
+        assert_eq!(
+            summary.inverter_types,
+            HashMap::from([(InverterType::Battery, 2), (InverterType::Solar, 3)])
+        );
+        assert_eq!(
+            summary.meter_roles,
+            HashMap::from([
+                (MeterRole::Grid, 1),
+                (MeterRole::Battery, 1),
+                (MeterRole::Pv, 1),
+                (MeterRole::EvCharger, 1),
+            ])
+        );
+
+        let mut fleet = GraphSummary::default();
+        fleet.merge(&summary);
+        fleet.merge(&summary);
+        assert_eq!(fleet.categories[&ComponentCategory::Meter], 8);
+        assert_eq!(fleet.inverter_types[&InverterType::Solar], 6);
+        assert_eq!(fleet.meter_roles[&MeterRole::Grid], 2);
+
+        Ok(())
+    }
+}
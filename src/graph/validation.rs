@@ -3,11 +3,12 @@
 
 //! Methods for validating a [`ComponentGraph`].
 
-mod invariant_checks;
 mod validate_graph;
 mod validate_neighbors;
+mod validate_phases;
+mod validate_ratings;
 
-use crate::{ComponentGraph, Edge, Error, Node};
+use crate::{ComponentGraph, ComponentGraphConfig, Edge, Error, ErrorKind, Node};
 
 pub(crate) struct ComponentGraphValidator<'a, N, E>
 where
@@ -18,31 +19,564 @@ where
     root: &'a N,
 }
 
+/// The result of validating a set of components and connections.
+///
+/// Unlike [`ComponentGraph::try_new`], which stops at the first failure and
+/// returns it as the error, a `ValidationReport` lists the failures of all the
+/// validation steps that could be run.
+///
+/// Problems that don't make the graph invalid, like inconsistent component
+/// ratings, or components that were only accepted because of assumptions
+/// allowed by the config, are listed separately as warnings.
+#[derive(Debug, Default, PartialEq)]
+pub struct ValidationReport {
+    errors: Vec<Error>,
+    warnings: Vec<Error>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no validation failures were found.
+    ///
+    /// Warnings don't affect the validity of the graph.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns the validation failures that were found.
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    /// Returns the warnings that were found.
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
+    /// Converts the report into a `Result`.
+    ///
+    /// If there is a single validation failure, it is returned as the error.
+    /// If there are multiple, they are combined into one `InvalidGraph` error,
+    /// and remain accessible through [`Error::errors`].
+    pub fn into_result(mut self) -> Result<(), Error> {
+        match self.errors.len() {
+            0 => Ok(()),
+            1 => Err(self.errors.remove(0)),
+            _ => Err(Error::multiple(self.errors)),
+        }
+    }
+}
+
+/// Runs all the checks that [`ComponentGraph::try_new_with_config`] would run
+/// on the given components and connections, without keeping the graph.
+///
+/// This is meant for tools that only want to check a site configuration.
+///
+/// If the graph can't be built at all, for example because of duplicate
+/// component IDs, the report contains only that failure.  Otherwise, the
+/// neighbor checks for all component categories are run, and their failures
+/// are all included in the report.
+pub fn validate_components<N, E>(
+    components: impl IntoIterator<Item = N>,
+    connections: impl IntoIterator<Item = E>,
+    config: ComponentGraphConfig,
+) -> ValidationReport
+where
+    N: Node,
+    E: Edge,
+{
+    match ComponentGraph::try_build(components, connections, config) {
+        Ok(cg) => cg.revalidate(),
+        Err(err) => ValidationReport {
+            errors: vec![err],
+            ..Default::default()
+        },
+    }
+}
+
 impl<N, E> ComponentGraph<N, E>
 where
     N: Node,
     E: Edge,
 {
+    /// Runs the checks that make the graph invalid, and returns the first
+    /// failure.
+    ///
+    /// The warning checks are skipped, because their results would be
+    /// discarded.
     pub(crate) fn validate(&self) -> Result<(), Error> {
-        let Ok(root) = self.component(self.root_id) else {
-            return Err(Error::internal(format!(
+        let validator = self.validator()?;
+        validator
+            .validate_structure()
+            .and_then(|_| validator.first_error())
+            .map_err(|err| self.with_configured_snippet(err))
+    }
+
+    /// Checks that the graph is acyclic, and that all components are
+    /// connected to the grid, without running the checks for the neighbors
+    /// of each component category.
+    ///
+    /// This is for graphs that are restored from data that was already
+    /// validated, because other methods rely on these properties.
+    #[cfg_attr(not(feature = "binary"), allow(dead_code))]
+    pub(crate) fn validate_structure(&self) -> Result<(), Error> {
+        self.validator()?
+            .validate_structure()
+            .map_err(|err| self.with_configured_snippet(err))
+    }
+
+    /// Runs all validation steps on the graph again, using the configuration
+    /// the graph was created with, and returns a report of their failures.
+    ///
+    /// Graphs returned by [`ComponentGraph::try_new`] have already been
+    /// validated, but the components they hold can report different
+    /// categories over time, for example when they wrap externally updated
+    /// data.  This method can be used to check that the invariants still hold.
+    ///
+    /// The acyclicity and connectedness checks need to pass before the
+    /// neighbor checks can be run, so if one of them fails, it is the only
+    /// failure in the report.
+    ///
+    /// The cached roles of the meters are cleared, so that they are computed
+    /// from the current categories when they are next requested.
+    ///
+    /// With the `tracing` feature, this runs in a
+    /// `ComponentGraph::revalidate` span, with the number of components, the
+    /// number of errors and warnings found, and the time it took.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "ComponentGraph::revalidate",
+            level = "debug",
+            skip_all,
+            fields(
+                components = self.graph.node_count(),
+                errors = tracing::field::Empty,
+                warnings = tracing::field::Empty,
+                elapsed_us = tracing::field::Empty,
+            )
+        )
+    )]
+    pub fn revalidate(&self) -> ValidationReport {
+        #[cfg(feature = "tracing")]
+        let _elapsed = super::ElapsedRecorder::start();
+
+        self.meter_roles.clear();
+        let report = self.with_error_snippets(self.validation_report());
+
+        #[cfg(feature = "tracing")]
+        tracing::Span::current()
+            .record("errors", report.errors.len())
+            .record("warnings", report.warnings.len());
+
+        report
+    }
+
+    /// Attaches neighborhood snippets to the errors of the report, if the
+    /// config asks for them.
+    fn with_error_snippets(&self, mut report: ValidationReport) -> ValidationReport {
+        report.errors = report
+            .errors
+            .into_iter()
+            .map(|err| self.with_configured_snippet(err))
+            .collect();
+        report
+    }
+
+    /// Attaches a neighborhood snippet to the error, if the config asks for
+    /// it.
+    fn with_configured_snippet(&self, err: Error) -> Error {
+        if self.config.include_error_snippets {
+            self.with_neighborhood_snippet(err)
+        } else {
+            err
+        }
+    }
+
+    /// Attaches a Mermaid flowchart of the first component of the given
+    /// error, with its predecessors and successors, to `InvalidGraph` errors.
+    fn with_neighborhood_snippet(&self, err: Error) -> Error {
+        if err.kind() != ErrorKind::InvalidGraph {
+            return err;
+        }
+        let Some(&cid) = err.component_ids().first() else {
+            return err;
+        };
+        let (Ok(component), Ok(predecessors), Ok(successors)) = (
+            self.component(cid),
+            self.predecessors(cid),
+            self.successors(cid),
+        ) else {
+            return err;
+        };
+
+        let node = |n: &N| {
+            format!(
+                "c{}[\"{}:{}\"]",
+                n.component_id(),
+                n.category(),
+                n.component_id()
+            )
+        };
+        let mut edges = predecessors
+            .map(|p| format!("    {} --> {}\n", node(p), node(component)))
+            .chain(successors.map(|s| format!("    {} --> {}\n", node(component), node(s))))
+            .peekable();
+        let mut snippet = String::from("flowchart TD\n");
+        if edges.peek().is_none() {
+            snippet.push_str(&format!("    {}\n", node(component)));
+        }
+        snippet.extend(edges);
+        snippet.push_str(&format!("    style c{cid} stroke:#d00,stroke-width:3px\n"));
+        err.with_snippet(snippet)
+    }
+
+    /// Returns a validator for the graph, or an error if the root component
+    /// is missing.
+    fn validator(&self) -> Result<ComponentGraphValidator<'_, N, E>, Error> {
+        let root = self.component(self.root_id).map_err(|_| {
+            Error::internal(format!(
                 "Grid component not found with detected component ID: {}.",
                 self.root_id
-            )));
+            ))
+            .with_components([self.root_id])
+        })?;
+        Ok(ComponentGraphValidator { cg: self, root })
+    }
+
+    fn validation_report(&self) -> ValidationReport {
+        let validator = match self.validator() {
+            Ok(validator) => validator,
+            Err(err) => {
+                return ValidationReport {
+                    errors: vec![err],
+                    ..Default::default()
+                }
+            }
         };
+        if let Err(err) = validator.validate_structure() {
+            return ValidationReport {
+                errors: vec![err],
+                ..Default::default()
+            };
+        }
 
-        let validator = ComponentGraphValidator { cg: self, root };
+        ValidationReport {
+            errors: validator.errors(),
+            warnings: validator.warnings(),
+        }
+    }
+}
 
-        validator.validate_acyclicity(root, vec![])?;
-        validator.validate_connected_graph(root)?;
+impl<'a, N, E> ComponentGraphValidator<'a, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// The checks that make the graph invalid when they fail, after the
+    /// acyclicity and connectedness checks have passed.
+    ///
+    /// Each is listed with the name of its step, so that rules shared
+    /// between steps, like `leaf`, can be told apart.
+    #[allow(clippy::type_complexity)]
+    const ERROR_STEPS: [(&'static str, fn(&Self) -> Result<(), Error>); 12] = [
+        ("validate_root", Self::validate_root),
+        (
+            "validate_exclusive_successors",
+            Self::validate_exclusive_successors,
+        ),
+        ("validate_meters", Self::validate_meters),
+        ("validate_inverters", Self::validate_inverters),
+        ("validate_batteries", Self::validate_batteries),
+        ("validate_ev_chargers", Self::validate_ev_chargers),
+        ("validate_chps", Self::validate_chps),
+        ("validate_fuses", Self::validate_fuses),
+        ("validate_battery_modules", Self::validate_battery_modules),
+        ("validate_pv_strings", Self::validate_pv_strings),
+        ("validate_sensors", Self::validate_sensors),
+        ("validate_other_categories", Self::validate_other_categories),
+    ];
+
+    /// The checks for problems that don't make the graph invalid.
+    ///
+    /// These are only run for validation reports, and not when a graph is
+    /// created, because some of them need to search the graph.
+    #[allow(clippy::type_complexity)]
+    const WARNING_STEPS: [(&'static str, fn(&Self) -> Vec<Error>); 5] = [
+        ("validate_assumptions", Self::validate_assumptions),
+        ("validate_rated_power", Self::validate_rated_power),
+        (
+            "validate_connection_ratings",
+            Self::validate_connection_ratings,
+        ),
+        ("validate_phase_balance", Self::validate_phase_balance),
+        (
+            "validate_redundant_connections",
+            Self::validate_redundant_connections,
+        ),
+    ];
+
+    /// Checks that the graph is acyclic and connected.
+    ///
+    /// These need to pass before any of the other checks can be run.
+    fn validate_structure(&self) -> Result<(), Error> {
+        self.validate_acyclicity(self.root)
+            .map_err(|e| e.in_rule_scope("validate_acyclicity"))?;
+        self.validate_connected_graph(self.root)
+            .map_err(|e| e.in_rule_scope("validate_connected_graph"))
+    }
+
+    /// Returns the failures of all the checks in [`Self::ERROR_STEPS`].
+    fn errors(&self) -> Vec<Error> {
+        Self::ERROR_STEPS
+            .into_iter()
+            .filter_map(|(step, check)| check(self).err().map(|e| e.in_rule_scope(step)))
+            .collect()
+    }
+
+    /// Runs the checks in [`Self::ERROR_STEPS`] until one of them fails.
+    fn first_error(&self) -> Result<(), Error> {
+        Self::ERROR_STEPS
+            .into_iter()
+            .try_for_each(|(step, check)| check(self).map_err(|e| e.in_rule_scope(step)))
+    }
+
+    /// Returns the warnings of all the warning checks.
+    fn warnings(&self) -> Vec<Error> {
+        Self::WARNING_STEPS
+            .into_iter()
+            .flat_map(|(step, check)| check(self).into_iter().map(|e| e.in_rule_scope(step)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ComponentCategory;
+    use crate::InverterType;
+
+    #[derive(Clone)]
+    struct TestComponent(u64, ComponentCategory);
+
+    impl Node for TestComponent {
+        fn component_id(&self) -> u64 {
+            self.0
+        }
+
+        fn category(&self) -> ComponentCategory {
+            self.1
+        }
+
+        fn is_supported(&self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Clone)]
+    struct TestConnection(u64, u64);
+
+    impl TestConnection {
+        fn new(source: u64, destination: u64) -> Self {
+            TestConnection(source, destination)
+        }
+    }
 
-        validator.validate_root()?;
-        validator.validate_meters()?;
-        validator.validate_inverters()?;
-        validator.validate_batteries()?;
-        validator.validate_ev_chargers()?;
-        validator.validate_chps()?;
+    impl Edge for TestConnection {
+        fn source(&self) -> u64 {
+            self.0
+        }
+
+        fn destination(&self) -> u64 {
+            self.1
+        }
+    }
+
+    #[test]
+    fn test_validate_components() {
+        let mut components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Meter),
+            TestComponent(3, ComponentCategory::Inverter(InverterType::Battery)),
+            TestComponent(4, ComponentCategory::Battery),
+        ];
+        let mut connections = vec![
+            TestConnection::new(1, 2),
+            TestConnection::new(2, 3),
+            TestConnection::new(3, 4),
+        ];
+
+        let report = validate_components(
+            components.clone(),
+            connections.clone(),
+            ComponentGraphConfig::default(),
+        );
+        assert!(report.is_valid());
+        assert_eq!(report.errors(), &[]);
+
+        // A battery directly under a meter, and a battery inverter without a
+        // battery are both reported.
+        components.push(TestComponent(5, ComponentCategory::Battery));
+        connections.push(TestConnection::new(2, 5));
+        components.push(TestComponent(
+            6,
+            ComponentCategory::Inverter(InverterType::Battery),
+        ));
+        connections.push(TestConnection::new(2, 6));
+
+        let report = validate_components(
+            components.clone(),
+            connections.clone(),
+            ComponentGraphConfig::default(),
+        );
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.errors(),
+            &[
+                Error::invalid_graph(
+                    "Meter:2 can't have successors with categories [Battery]. Found Battery:5."
+                ),
+                Error::invalid_graph("BatteryInverter:6 must have at least one successor."),
+                Error::invalid_graph(concat!(
+                    "Battery:5 can only have predecessors with categories: ",
+                    "[BatteryInverter, HybridInverter]. Found Meter:2."
+                )),
+            ]
+        );
+        assert_eq!(
+            report.errors().iter().map(|e| e.rule()).collect::<Vec<_>>(),
+            [
+                Some("validate_meters::successor_not_categories"),
+                Some("validate_inverters::not_leaf"),
+                Some("validate_batteries::predecessor_categories"),
+            ]
+        );
+        let errors = report.errors().to_vec();
+        let Err(err) = report.into_result() else {
+            panic!("Expected an error");
+        };
+        assert_eq!(err.errors(), errors);
+        assert_eq!(
+            err.to_string(),
+            concat!(
+                "InvalidGraph: Multiple validation failures:\n",
+                "    InvalidGraph: Meter:2 can't have successors with categories [Battery]. ",
+                "Found Battery:5.\n",
+                "    InvalidGraph: BatteryInverter:6 must have at least one successor.\n",
+                "    InvalidGraph: Battery:5 can only have predecessors with categories: ",
+                "[BatteryInverter, HybridInverter]. Found Meter:2."
+            )
+        );
+
+        // Failures that prevent the graph from being built are reported
+        // alone.
+        connections.push(TestConnection::new(2, 7));
+        let report = validate_components(components, connections, ComponentGraphConfig::default());
+        assert_eq!(
+            report.errors(),
+            &[Error::invalid_connection(
+                "Connection:(2, 7) Can't find a component with ID 7"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_revalidate() -> Result<(), Error> {
+        use std::cell::Cell;
+
+        /// A component whose category can be changed after the graph is
+        /// created.
+        struct MutableComponent(u64, Cell<ComponentCategory>);
+
+        impl Node for MutableComponent {
+            fn component_id(&self) -> u64 {
+                self.0
+            }
+
+            fn category(&self) -> ComponentCategory {
+                self.1.get()
+            }
+
+            fn is_supported(&self) -> bool {
+                true
+            }
+        }
+
+        let components = vec![
+            MutableComponent(1, Cell::new(ComponentCategory::Grid)),
+            MutableComponent(2, Cell::new(ComponentCategory::Meter)),
+            MutableComponent(3, Cell::new(ComponentCategory::Chp)),
+        ];
+        let connections = vec![TestConnection::new(1, 2), TestConnection::new(2, 3)];
+
+        let graph = ComponentGraph::try_new(components, connections)?;
+        assert!(graph.revalidate().is_valid());
+
+        graph.component(2)?.1.set(ComponentCategory::Chp);
+        assert_eq!(
+            graph.revalidate().errors(),
+            &[Error::invalid_graph(
+                "CHP:2 can't have any successors. Found CHP:3."
+            )]
+        );
 
         Ok(())
     }
+
+    #[test]
+    fn test_error_snippets() {
+        let components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Meter),
+            TestComponent(3, ComponentCategory::Inverter(InverterType::Battery)),
+            TestComponent(4, ComponentCategory::Meter),
+        ];
+        let connections = vec![
+            TestConnection::new(1, 2),
+            TestConnection::new(2, 3),
+            TestConnection::new(3, 4),
+        ];
+
+        let report = validate_components(
+            components.clone(),
+            connections.clone(),
+            ComponentGraphConfig::default(),
+        );
+        assert!(report.errors().iter().all(|e| e.snippet().is_none()));
+
+        let report = validate_components(
+            components,
+            connections,
+            ComponentGraphConfig {
+                include_error_snippets: true,
+                ..Default::default()
+            },
+        );
+        let snippets = report
+            .errors()
+            .iter()
+            .map(|e| (e.component_ids()[0], e.snippet()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            snippets,
+            [
+                (
+                    4,
+                    Some(concat!(
+                        "flowchart TD\n",
+                        "    c3[\"BatteryInverter:3\"] --> c4[\"Meter:4\"]\n",
+                        "    style c4 stroke:#d00,stroke-width:3px\n",
+                    ))
+                ),
+                (
+                    3,
+                    Some(concat!(
+                        "flowchart TD\n",
+                        "    c2[\"Meter:2\"] --> c3[\"BatteryInverter:3\"]\n",
+                        "    c3[\"BatteryInverter:3\"] --> c4[\"Meter:4\"]\n",
+                        "    style c3 stroke:#d00,stroke-width:3px\n",
+                    ))
+                ),
+            ]
+        );
+    }
 }
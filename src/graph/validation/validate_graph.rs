@@ -4,9 +4,9 @@
 //! Methods for validating the acyclicity and connectedness of a
 //! [`ComponentGraph`].
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
-use crate::{Edge, Error, Node};
+use crate::{invariants, Edge, Error, Node, Reachability};
 
 use super::ComponentGraphValidator;
 
@@ -22,8 +22,9 @@ where
         visited.insert(root_id);
         while let Some(node_id) = queue.pop() {
             for successor in self.cg.successors(node_id)? {
-                visited.insert(successor.component_id());
-                queue.push(successor.component_id());
+                if visited.insert(successor.component_id()) {
+                    queue.push(successor.component_id());
+                }
             }
         }
 
@@ -38,35 +39,160 @@ where
             return Err(Error::invalid_graph(format!(
                 "Nodes {:?} are not connected to the root.",
                 unvisited
-            )));
+            ))
+            .with_components(unvisited)
+            .with_rule("connected"));
         }
 
         Ok(())
     }
 
-    pub(super) fn validate_acyclicity(
+    /// Checks that the successors of the components at the levels listed in
+    /// [`exclusive_successor_levels`][crate::ComponentGraphConfig::exclusive_successor_levels]
+    /// don't have any other predecessors.
+    pub(super) fn validate_exclusive_successors(&self) -> Result<(), Error> {
+        let levels = &self.cg.config.exclusive_successor_levels;
+        let Some(&max_level) = levels.last() else {
+            return Ok(());
+        };
+
+        let mut visited = BTreeSet::from([self.root.component_id()]);
+        let mut queue = VecDeque::from([(self.root, 0)]);
+        while let Some((node, level)) = queue.pop_front() {
+            if levels.contains(&level) {
+                invariants::ensure_exclusive_successors(self.cg, node)?;
+            }
+            if level == max_level {
+                continue;
+            }
+            for successor in self.cg.successors(node.component_id())? {
+                if visited.insert(successor.component_id()) {
+                    queue.push_back((successor, level + 1));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finds connections that duplicate an indirect path between the same two
+    /// components, like `grid -> inverter` when `grid -> meter -> inverter`
+    /// also exists.
+    ///
+    /// Such connections usually come from bad source data, so they are
+    /// returned as warnings.
+    pub(super) fn validate_redundant_connections(&self) -> Vec<Error> {
+        // The components below each component are computed once, so that
+        // each connection can be checked without searching the graph.
+        let Ok(reachability) = self.cg.reachability() else {
+            return vec![];
+        };
+        let mut warnings = vec![];
+        for connection in self.cg.connections() {
+            let (sid, did) = (connection.source(), connection.destination());
+            // An indirect path to `did` has to arrive through another
+            // predecessor, so most connections can be skipped right away.
+            if self.cg.predecessors(did).map_or(0, |p| p.count()) < 2 {
+                continue;
+            }
+            if let Some(path) = self.find_indirect_path(&reachability, sid, did) {
+                warnings.push(Error::redundant_connection(format!(
+                    "Connection:({sid}, {did}) is redundant, because {did} is also reachable from {sid} through: {}",
+                    path.iter()
+                        .map(|x| x.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" -> ")
+                ))
+                .with_connection(sid, did)
+                .with_components(path)
+                .with_rule("redundant_connections"));
+            }
+        }
+        warnings
+    }
+
+    /// Returns a path from `source` to `destination` with at least one
+    /// component in between, if there is one.
+    ///
+    /// The path follows the first successor that leads to `destination` at
+    /// each step.
+    fn find_indirect_path(
+        &self,
+        reachability: &Reachability<'_, N, E>,
+        source: u64,
+        destination: u64,
+    ) -> Option<Vec<u64>> {
+        let leads_to_destination = |id: u64| {
+            reachability
+                .is_upstream_of(id, destination)
+                .unwrap_or(false)
+        };
+        let mut path = vec![source];
+        let mut current = self
+            .cg
+            .successors(source)
+            .ok()?
+            .map(|n| n.component_id())
+            .find(|&id| id != destination && leads_to_destination(id))?;
+        while current != destination {
+            path.push(current);
+            current = self
+                .cg
+                .successors(current)
+                .ok()?
+                .map(|n| n.component_id())
+                .find(|&id| id == destination || leads_to_destination(id))?;
+        }
+        path.push(destination);
+        Some(path)
+    }
+
+    pub(super) fn validate_acyclicity(&self, root: &N) -> Result<(), Error> {
+        self.find_cycle(
+            root,
+            &mut vec![],
+            &mut BTreeMap::new(),
+            &mut BTreeSet::new(),
+        )
+    }
+
+    /// Searches for cycles reachable from `node`, which is reached through
+    /// `path`.
+    ///
+    /// `positions` holds the position of each component of `path`, so that
+    /// cycles are found without scanning the path.  Components whose
+    /// successors have already been searched are added to `done`, so that
+    /// they are not searched again when reached through a different path.
+    fn find_cycle(
         &self,
         node: &N,
-        mut predecessors: Vec<u64>,
+        path: &mut Vec<u64>,
+        positions: &mut BTreeMap<u64, usize>,
+        done: &mut BTreeSet<u64>,
     ) -> Result<(), Error> {
-        predecessors.push(node.component_id());
+        positions.insert(node.component_id(), path.len());
+        path.push(node.component_id());
         for successor in self.cg.successors(node.component_id())? {
-            if let Some(first_occurance) = predecessors
-                .iter()
-                .position(|id| *id == successor.component_id())
-            {
+            if let Some(&first_occurance) = positions.get(&successor.component_id()) {
                 return Err(Error::invalid_graph(format!(
                     "Cycle detected: {} -> {}",
-                    predecessors[first_occurance..]
+                    path[first_occurance..]
                         .iter()
                         .map(|x| x.to_string())
                         .collect::<Vec<_>>()
                         .join(" -> "),
                     successor.component_id()
-                )));
+                ))
+                .with_components(path[first_occurance..].iter().copied())
+                .with_rule("acyclic"));
+            }
+            if !done.contains(&successor.component_id()) {
+                self.find_cycle(successor, path, positions, done)?;
             }
-            self.validate_acyclicity(successor, predecessors.clone())?;
         }
+        path.pop();
+        positions.remove(&node.component_id());
+        done.insert(node.component_id());
         Ok(())
     }
 }
@@ -76,6 +202,7 @@ mod tests {
     use super::*;
     use crate::ComponentCategory;
     use crate::ComponentGraph;
+    use crate::ComponentGraphConfig;
     use crate::InverterType;
 
     #[derive(Clone)]
@@ -87,7 +214,7 @@ mod tests {
         }
 
         fn category(&self) -> ComponentCategory {
-            self.1.clone()
+            self.1
         }
 
         fn is_supported(&self) -> bool {
@@ -191,8 +318,10 @@ mod tests {
         // add cycles at different levels
         connections.push(TestConnection::new(3, 2));
         assert!(
-            ComponentGraph::try_new(components.clone(), connections.clone())
-                .is_err_and(|e| e == Error::invalid_graph("Cycle detected: 2 -> 3 -> 2")),
+            ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| e
+                == Error::invalid_connection(
+                    "Connection:(3, 2) Conflicts with the reversed connection (2, 3)."
+                )),
         );
 
         connections.pop();
@@ -212,8 +341,10 @@ mod tests {
         connections.pop();
         connections.push(TestConnection::new(4, 3));
         assert!(
-            ComponentGraph::try_new(components.clone(), connections.clone())
-                .is_err_and(|e| e == Error::invalid_graph("Cycle detected: 3 -> 4 -> 3"))
+            ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| e
+                == Error::invalid_connection(
+                    "Connection:(4, 3) Conflicts with the reversed connection (3, 4)."
+                ))
         );
 
         connections.pop();
@@ -226,18 +357,136 @@ mod tests {
         connections.pop();
         connections.push(TestConnection::new(5, 4));
         assert!(
-            ComponentGraph::try_new(components.clone(), connections.clone())
-                .is_err_and(|e| e == Error::invalid_graph("Cycle detected: 4 -> 5 -> 4"))
+            ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| e
+                == Error::invalid_connection(
+                    "Connection:(5, 4) Conflicts with the reversed connection (4, 5)."
+                ))
         );
 
         connections.pop();
         connections.push(TestConnection::new(9, 2));
         assert!(
-            ComponentGraph::try_new(components.clone(), connections.clone())
-                .is_err_and(|e| e == Error::invalid_graph("Cycle detected: 2 -> 9 -> 2"))
+            ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| e
+                == Error::invalid_connection(
+                    "Connection:(9, 2) Conflicts with the reversed connection (2, 9)."
+                ))
         );
 
         connections.pop();
         assert!(ComponentGraph::try_new(components.clone(), connections.clone()).is_ok());
     }
+
+    #[test]
+    fn test_stacked_diamonds_validation() {
+        // A chain of diamonds has exponentially many paths from the root, so
+        // this only finishes if each component is visited once.
+        let mut components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Meter),
+        ];
+        let mut connections = vec![TestConnection::new(1, 2)];
+        let mut top = 2;
+        for _ in 0..40 {
+            let (left, right, bottom) = (top + 1, top + 2, top + 3);
+            for id in [left, right, bottom] {
+                components.push(TestComponent(id, ComponentCategory::Meter));
+            }
+            connections.extend([
+                TestConnection::new(top, left),
+                TestConnection::new(top, right),
+                TestConnection::new(left, bottom),
+                TestConnection::new(right, bottom),
+            ]);
+            top = bottom;
+        }
+
+        assert!(ComponentGraph::try_new(components, connections).is_ok());
+    }
+
+    #[test]
+    fn test_exclusive_successors_validation() {
+        let (components, mut connections) = nodes_and_edges();
+
+        // Meter 3 is now both a successor of meter 2 and of meter 9.
+        connections.push(TestConnection::new(9, 3));
+        assert!(ComponentGraph::try_new(components.clone(), connections.clone()).is_ok());
+
+        let config = ComponentGraphConfig {
+            exclusive_successor_levels: BTreeSet::from([0, 1]),
+            ..Default::default()
+        };
+        assert!(ComponentGraph::try_new_with_config(
+            components.clone(),
+            connections.clone(),
+            config.clone()
+        )
+        .is_err_and(|e| e
+            == Error::invalid_graph(
+                "Meter:2 can't have successors with multiple predecessors. Found Meter:3."
+            )));
+
+        connections.pop();
+        assert!(ComponentGraph::try_new_with_config(
+            components.clone(),
+            connections.clone(),
+            config
+        )
+        .is_ok());
+
+        // A diamond directly beneath the grid is only allowed when the check
+        // is disabled for level 0.
+        let components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Meter),
+            TestComponent(3, ComponentCategory::Meter),
+        ];
+        let connections = vec![
+            TestConnection::new(1, 2),
+            TestConnection::new(1, 3),
+            TestConnection::new(2, 3),
+        ];
+        assert!(
+            ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| e
+                == Error::invalid_graph(
+                    "Grid:1 can't have successors with multiple predecessors. Found Meter:3."
+                ))
+        );
+        assert!(ComponentGraph::try_new_with_config(
+            components,
+            connections,
+            ComponentGraphConfig {
+                exclusive_successor_levels: BTreeSet::new(),
+                ..Default::default()
+            }
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_redundant_connections_validation() -> Result<(), Error> {
+        let (components, mut connections) = nodes_and_edges();
+
+        let graph = ComponentGraph::try_new(components.clone(), connections.clone())?;
+        assert_eq!(graph.revalidate().warnings(), &[]);
+
+        connections.push(TestConnection::new(2, 4));
+        connections.push(TestConnection::new(2, 10));
+
+        let graph = ComponentGraph::try_new(components, connections)?;
+        assert_eq!(
+            graph.revalidate().warnings(),
+            &[
+                Error::redundant_connection(concat!(
+                    "Connection:(2, 4) is redundant, because 4 is also reachable ",
+                    "from 2 through: 2 -> 3 -> 4"
+                )),
+                Error::redundant_connection(concat!(
+                    "Connection:(2, 10) is redundant, because 10 is also reachable ",
+                    "from 2 through: 2 -> 9 -> 10"
+                )),
+            ]
+        );
+
+        Ok(())
+    }
 }
@@ -5,7 +5,8 @@
 //! connected correctly.
 
 use crate::{
-    component_category::CategoryPredicates, ComponentCategory, Edge, Error, InverterType, Node,
+    component_category::CategoryPredicates, invariants, ComponentCategory, Edge, Error,
+    InverterType, Node, OtherCategoryPolicy,
 };
 
 use super::ComponentGraphValidator;
@@ -15,52 +16,106 @@ where
     N: Node,
     E: Edge,
 {
+    /// Returns the categories of the components that can be predecessors of
+    /// metered components like inverters, EV chargers and CHPs.
+    ///
+    /// These are meters, the grid and the grid fuse, and the categories
+    /// configured to be transparent.
+    fn metered_categories(&self) -> Vec<ComponentCategory> {
+        let mut categories = vec![
+            ComponentCategory::Meter,
+            ComponentCategory::Grid,
+            ComponentCategory::Fuse,
+        ];
+        categories.extend(self.cg.config.transparent_categories());
+        categories
+    }
+
     pub(super) fn validate_root(&self) -> Result<(), Error> {
-        self.ensure_root(self.root)?;
-        self.ensure_not_leaf(self.root)?;
-        self.ensure_exclusive_successors(self.root)?;
+        invariants::ensure_root(self.cg, self.root)?;
+        invariants::ensure_not_leaf(self.cg, self.root)?;
 
         Ok(())
     }
 
     pub(super) fn validate_meters(&self) -> Result<(), Error> {
         for meter in self.cg.components().filter(|n| n.is_meter()) {
-            self.ensure_predecessor_categories(
+            invariants::ensure_predecessor_categories(
+                self.cg,
                 meter,
-                &[ComponentCategory::Grid, ComponentCategory::Meter],
+                &[
+                    ComponentCategory::Grid,
+                    ComponentCategory::Meter,
+                    ComponentCategory::Fuse,
+                ],
             )?;
-            self.ensure_successor_not_categories(meter, &[ComponentCategory::Battery])?;
+            if !self.cg.config.allow_batteries_under_meters {
+                invariants::ensure_successor_not_categories(
+                    self.cg,
+                    meter,
+                    &[ComponentCategory::Battery],
+                )?;
+            }
         }
         Ok(())
     }
 
     pub(super) fn validate_inverters(&self) -> Result<(), Error> {
         for inverter in self.cg.components().filter(|n| n.is_inverter()) {
-            let ComponentCategory::Inverter(inverter_type) = inverter.category() else {
+            let ComponentCategory::Inverter(inverter_type) =
+                self.cg.config.effective_category(inverter.category())
+            else {
                 continue;
             };
 
-            self.ensure_predecessor_categories(
+            invariants::ensure_predecessor_categories(
+                self.cg,
                 inverter,
-                &[ComponentCategory::Meter, ComponentCategory::Grid],
+                &self.metered_categories(),
             )?;
 
             match inverter_type {
                 InverterType::Battery => {
-                    self.ensure_not_leaf(inverter)?;
-                    self.ensure_successor_categories(inverter, &[ComponentCategory::Battery])?;
+                    // Configured successor rules replace the requirement to
+                    // have a battery.
+                    if !self
+                        .cg
+                        .config
+                        .successor_rules
+                        .contains_key(&ComponentCategory::Inverter(inverter_type))
+                    {
+                        invariants::ensure_not_leaf(self.cg, inverter)?;
+                    }
+                    invariants::ensure_allowed_successors(
+                        self.cg,
+                        inverter,
+                        &[ComponentCategory::Battery],
+                    )?;
                 }
                 InverterType::Solar => {
-                    self.ensure_leaf(inverter)?;
+                    invariants::ensure_allowed_successors(
+                        self.cg,
+                        inverter,
+                        &[ComponentCategory::PvString],
+                    )?;
+                }
+                InverterType::Wind => {
+                    invariants::ensure_allowed_successors(self.cg, inverter, &[])?;
                 }
                 InverterType::Hybrid => {
-                    self.ensure_successor_categories(inverter, &[ComponentCategory::Battery])?;
+                    invariants::ensure_allowed_successors(
+                        self.cg,
+                        inverter,
+                        &[ComponentCategory::Battery, ComponentCategory::PvString],
+                    )?;
                 }
                 InverterType::Unspecified => {
                     return Err(Error::invalid_graph(format!(
                         "Inverter {} has an unspecified inverter type.",
                         inverter.component_id()
-                    )));
+                    ))
+                    .with_components([inverter.component_id()])
+                    .with_rule("inverter_type"));
                 }
             }
         }
@@ -70,36 +125,238 @@ where
 
     pub(super) fn validate_batteries(&self) -> Result<(), Error> {
         for battery in self.cg.components().filter(|n| n.is_battery()) {
-            self.ensure_leaf(battery)?;
-            self.ensure_predecessor_categories(
+            invariants::ensure_allowed_successors(
+                self.cg,
                 battery,
-                &[
-                    ComponentCategory::Inverter(InverterType::Battery),
-                    ComponentCategory::Inverter(InverterType::Hybrid),
-                ],
+                &[ComponentCategory::BatteryModule],
             )?;
+            let mut predecessor_categories = vec![
+                ComponentCategory::Inverter(InverterType::Battery),
+                ComponentCategory::Inverter(InverterType::Hybrid),
+            ];
+            if self.cg.config.allow_batteries_under_meters {
+                predecessor_categories.push(ComponentCategory::Meter);
+            }
+            invariants::ensure_predecessor_categories(self.cg, battery, &predecessor_categories)?;
+            self.ensure_consistent_battery_sharing(battery)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the inverters sharing the given battery are of the same
+    /// type and have the same predecessors, because the battery formulas
+    /// treat them as a single group.
+    fn ensure_consistent_battery_sharing(&self, battery: &N) -> Result<(), Error> {
+        let mut inverters = self
+            .cg
+            .predecessors(battery.component_id())?
+            .filter(|n| n.is_inverter());
+        let Some(first) = inverters.next() else {
+            return Ok(());
+        };
+        let predecessor_ids = |inverter: &N| -> Result<Vec<u64>, Error> {
+            Ok(self
+                .cg
+                .predecessors(inverter.component_id())?
+                .map(|n| n.component_id())
+                .collect())
+        };
+        let first_predecessors = predecessor_ids(first)?;
+        let effective_category = |n: &N| self.cg.config.effective_category(n.category());
+        for inverter in inverters {
+            if effective_category(inverter) != effective_category(first) {
+                return Err(Error::invalid_graph(format!(
+                    "{}:{} can't be shared by inverters of different types. Found {}:{} and {}:{}.",
+                    battery.category(),
+                    battery.component_id(),
+                    first.category(),
+                    first.component_id(),
+                    inverter.category(),
+                    inverter.component_id(),
+                ))
+                .with_components([
+                    battery.component_id(),
+                    first.component_id(),
+                    inverter.component_id(),
+                ])
+                .with_rule("shared_inverter_types"));
+            }
+            if predecessor_ids(inverter)? != first_predecessors {
+                return Err(Error::invalid_graph(format!(
+                    "{}:{} can't be shared by inverters with different predecessors. \
+                     Found {}:{} and {}:{}.",
+                    battery.category(),
+                    battery.component_id(),
+                    first.category(),
+                    first.component_id(),
+                    inverter.category(),
+                    inverter.component_id(),
+                ))
+                .with_components([
+                    battery.component_id(),
+                    first.component_id(),
+                    inverter.component_id(),
+                ])
+                .with_rule("shared_inverter_predecessors"));
+            }
         }
         Ok(())
     }
 
     pub(super) fn validate_ev_chargers(&self) -> Result<(), Error> {
         for ev_charger in self.cg.components().filter(|n| n.is_ev_charger()) {
-            self.ensure_leaf(ev_charger)?;
-            self.ensure_predecessor_categories(
+            invariants::ensure_allowed_successors(self.cg, ev_charger, &[])?;
+            invariants::ensure_predecessor_categories(
+                self.cg,
                 ev_charger,
-                &[ComponentCategory::Meter, ComponentCategory::Grid],
+                &self.metered_categories(),
+            )?;
+            if self.cg.config.validate_ev_charger_types && ev_charger.is_dc_ev_charger() {
+                invariants::ensure_predecessor_categories(
+                    self.cg,
+                    ev_charger,
+                    &[ComponentCategory::Meter],
+                )?;
+                invariants::ensure_exclusive_predecessors(self.cg, ev_charger)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn validate_sensors(&self) -> Result<(), Error> {
+        for sensor in self.cg.components().filter(|n| n.is_sensor()) {
+            invariants::ensure_allowed_successors(self.cg, sensor, &[])?;
+            invariants::ensure_predecessor_categories(
+                self.cg,
+                sensor,
+                &[ComponentCategory::Grid, ComponentCategory::Meter],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Battery modules can be nested, for packs that list their modules and
+    /// the cells of each module.
+    pub(super) fn validate_battery_modules(&self) -> Result<(), Error> {
+        for module in self.cg.components().filter(|n| n.is_battery_module()) {
+            invariants::ensure_allowed_successors(
+                self.cg,
+                module,
+                &[ComponentCategory::BatteryModule],
+            )?;
+            invariants::ensure_predecessor_categories(
+                self.cg,
+                module,
+                &[ComponentCategory::Battery, ComponentCategory::BatteryModule],
             )?;
         }
         Ok(())
     }
 
+    pub(super) fn validate_pv_strings(&self) -> Result<(), Error> {
+        for pv_string in self.cg.components().filter(|n| n.is_pv_string()) {
+            invariants::ensure_allowed_successors(self.cg, pv_string, &[])?;
+            invariants::ensure_predecessor_categories(
+                self.cg,
+                pv_string,
+                &[
+                    ComponentCategory::Inverter(InverterType::Solar),
+                    ComponentCategory::Inverter(InverterType::Hybrid),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// The grid fuse limits the grid connection, so it must sit directly
+    /// under the grid, and nowhere else, and there can only be one.
+    ///
+    /// Other fuses, like those protecting a branch of the site, can be
+    /// anywhere.
+    pub(super) fn validate_fuses(&self) -> Result<(), Error> {
+        let mut grid_fuses = self
+            .cg
+            .successors(self.root.component_id())?
+            .filter(|n| n.category().is_fuse())
+            .collect::<Vec<_>>();
+        grid_fuses.sort_by_key(|n| n.component_id());
+        if let [first, second, ..] = grid_fuses[..] {
+            return Err(Error::invalid_graph(format!(
+                "{}:{} can only have one fuse as a successor. Found {}:{} and {}:{}.",
+                self.root.category(),
+                self.root.component_id(),
+                first.category(),
+                first.component_id(),
+                second.category(),
+                second.component_id(),
+            ))
+            .with_components([
+                self.root.component_id(),
+                first.component_id(),
+                second.component_id(),
+            ])
+            .with_rule("single_grid_fuse"));
+        }
+        if let Some(fuse) = self.cg.grid_fuse() {
+            invariants::ensure_predecessor_categories(self.cg, fuse, &[ComponentCategory::Grid])?;
+        }
+        Ok(())
+    }
+
+    /// Returns a warning for each component that is accepted only because of
+    /// an assumption allowed by the config, like inverters with an
+    /// unspecified type, or components of unknown categories.
+    pub(super) fn validate_assumptions(&self) -> Vec<Error> {
+        let mut warnings = vec![];
+        for component in self.cg.components() {
+            let category = component.category();
+            let (desc, rule) = if component.is_unspecified_inverter() {
+                (
+                    format!(
+                        "{}:{} is treated as a {}.",
+                        category,
+                        component.component_id(),
+                        self.cg.config.effective_category(category)
+                    ),
+                    "unspecified_inverter_type",
+                )
+            } else if component.is_other() {
+                (
+                    format!(
+                        "{}:{} has a category unknown to this library, and is only partly validated.",
+                        category,
+                        component.component_id()
+                    ),
+                    "other_category",
+                )
+            } else {
+                continue;
+            };
+            warnings.push(
+                Error::assumption(desc)
+                    .with_components([component.component_id()])
+                    .with_rule(rule),
+            );
+        }
+        warnings
+    }
+
+    /// Components of unknown categories can only be checked as configured
+    /// with [`other_categories`][crate::ComponentGraphConfig::other_categories].
+    pub(super) fn validate_other_categories(&self) -> Result<(), Error> {
+        if self.cg.config.other_categories != OtherCategoryPolicy::LeafOnly {
+            return Ok(());
+        }
+        for component in self.cg.components().filter(|n| n.is_other()) {
+            invariants::ensure_leaf(self.cg, component)?;
+        }
+        Ok(())
+    }
+
     pub(super) fn validate_chps(&self) -> Result<(), Error> {
         for chp in self.cg.components().filter(|n| n.is_chp()) {
-            self.ensure_leaf(chp)?;
-            self.ensure_predecessor_categories(
-                chp,
-                &[ComponentCategory::Meter, ComponentCategory::Grid],
-            )?;
+            invariants::ensure_allowed_successors(self.cg, chp, &[])?;
+            invariants::ensure_predecessor_categories(self.cg, chp, &self.metered_categories())?;
         }
         Ok(())
     }
@@ -108,8 +365,11 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::validate_components;
     use crate::ComponentCategory;
     use crate::ComponentGraph;
+    use crate::ComponentGraphConfig;
+    use crate::EvChargerType;
     use crate::InverterType;
 
     #[derive(Clone)]
@@ -121,7 +381,7 @@ mod tests {
         }
 
         fn category(&self) -> ComponentCategory {
-            self.1.clone()
+            self.1
         }
 
         fn is_supported(&self) -> bool {
@@ -202,6 +462,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_batteries_under_meters() {
+        let components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Meter),
+            TestComponent(3, ComponentCategory::Battery),
+        ];
+        let connections = vec![TestConnection::new(1, 2), TestConnection::new(2, 3)];
+        let config = ComponentGraphConfig {
+            allow_batteries_under_meters: true,
+            ..Default::default()
+        };
+        assert!(
+            ComponentGraph::try_new_with_config(components, connections, config.clone()).is_ok()
+        );
+
+        let components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Battery),
+        ];
+        let connections = vec![TestConnection::new(1, 2)];
+        assert!(
+            ComponentGraph::try_new_with_config(components, connections, config).is_err_and(|e| {
+                e == Error::invalid_graph(concat!(
+                    "Battery:2 can only have predecessors with categories: ",
+                    "[BatteryInverter, HybridInverter, Meter]. Found Grid:1."
+                ))
+            }),
+        );
+    }
+
     #[test]
     fn test_validate_battery_inverter() {
         let mut components = vec![
@@ -239,7 +530,7 @@ mod tests {
     }
 
     #[test]
-    fn test_validate_pv_inverter() {
+    fn test_validate_pv_inverter() -> Result<(), Error> {
         let mut components = vec![
             TestComponent(1, ComponentCategory::Grid),
             TestComponent(2, ComponentCategory::Meter),
@@ -251,10 +542,66 @@ mod tests {
             TestConnection::new(2, 3),
             TestConnection::new(3, 4),
         ];
+        assert!(
+            ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| {
+                e == Error::invalid_graph(concat!(
+                    "SolarInverter:3 can only have successors with categories [PVString]. ",
+                    "Found Electrolyzer:4."
+                ))
+            }),
+        );
+
+        components.pop();
+        connections.pop();
+
+        assert!(ComponentGraph::try_new(components.clone(), connections.clone()).is_ok());
+
+        // PV strings are allowed beneath PV inverters, and can be found with
+        // `descendants`.
+        components.push(TestComponent(4, ComponentCategory::PvString));
+        components.push(TestComponent(5, ComponentCategory::PvString));
+        connections.push(TestConnection::new(3, 4));
+        connections.push(TestConnection::new(3, 5));
+        let graph = ComponentGraph::try_new(components.clone(), connections.clone())?;
+        assert_eq!(
+            graph
+                .descendants(2, None)?
+                .map(|n| n.component_id())
+                .collect::<Vec<_>>(),
+            [3, 4, 5]
+        );
+
+        connections.pop();
+        connections.push(TestConnection::new(2, 5));
+        assert!(
+            ComponentGraph::try_new(components, connections).is_err_and(|e| {
+                e == Error::invalid_graph(concat!(
+                    "PVString:5 can only have predecessors with categories: ",
+                    "[SolarInverter, HybridInverter]. Found Meter:2."
+                ))
+            }),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_wind_inverter() {
+        let mut components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Meter),
+            TestComponent(3, ComponentCategory::Inverter(InverterType::Wind)),
+            TestComponent(4, ComponentCategory::Electrolyzer),
+        ];
+        let mut connections = vec![
+            TestConnection::new(1, 2),
+            TestConnection::new(2, 3),
+            TestConnection::new(3, 4),
+        ];
         assert!(
             ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| {
                 e == Error::invalid_graph(
-                    "SolarInverter:3 can't have any successors. Found Electrolyzer:4.",
+                    "WindInverter:3 can't have any successors. Found Electrolyzer:4.",
                 )
             }),
         );
@@ -280,9 +627,10 @@ mod tests {
         ];
         assert!(
             ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| {
-                e == Error::invalid_graph(
-                    "HybridInverter:3 can only have successors with categories [Battery]. Found Electrolyzer:4.",
-                )
+                e == Error::invalid_graph(concat!(
+                    "HybridInverter:3 can only have successors with categories ",
+                    "[Battery, PVString]. Found Electrolyzer:4."
+                ))
             }),
         );
 
@@ -314,7 +662,10 @@ mod tests {
         ];
         assert!(
             ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| {
-                e == Error::invalid_graph("Battery:4 can't have any successors. Found Battery:5.")
+                e == Error::invalid_graph(concat!(
+                    "Battery:4 can only have successors with categories [BatteryModule]. ",
+                    "Found Battery:5."
+                ))
             }),
         );
 
@@ -323,6 +674,26 @@ mod tests {
 
         assert!(ComponentGraph::try_new(components.clone(), connections.clone()).is_ok());
 
+        // Battery modules are allowed beneath batteries and other modules.
+        let mut with_modules = components.clone();
+        let mut module_connections = connections.clone();
+        with_modules.push(TestComponent(5, ComponentCategory::BatteryModule));
+        with_modules.push(TestComponent(6, ComponentCategory::BatteryModule));
+        module_connections.push(TestConnection::new(4, 5));
+        module_connections.push(TestConnection::new(5, 6));
+        assert!(ComponentGraph::try_new(with_modules.clone(), module_connections.clone()).is_ok());
+
+        with_modules.push(TestComponent(7, ComponentCategory::BatteryModule));
+        module_connections.push(TestConnection::new(2, 7));
+        assert!(
+            ComponentGraph::try_new(with_modules, module_connections).is_err_and(|e| {
+                e == Error::invalid_graph(concat!(
+                    "BatteryModule:7 can only have predecessors with categories: ",
+                    "[Battery, BatteryModule]. Found Meter:2."
+                ))
+            }),
+        );
+
         components.pop();
         components.pop();
 
@@ -350,12 +721,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_battery_sharing() {
+        // Grid:1 -> Meter:2 -> BatteryInverter:3,4 -> Battery:5
+        let mut components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Meter),
+            TestComponent(3, ComponentCategory::Inverter(InverterType::Battery)),
+            TestComponent(4, ComponentCategory::Inverter(InverterType::Battery)),
+            TestComponent(5, ComponentCategory::Battery),
+        ];
+        let mut connections = vec![
+            TestConnection::new(1, 2),
+            TestConnection::new(2, 3),
+            TestConnection::new(2, 4),
+            TestConnection::new(3, 5),
+            TestConnection::new(4, 5),
+        ];
+        assert!(ComponentGraph::try_new(components.clone(), connections.clone()).is_ok());
+
+        components[3].1 = ComponentCategory::Inverter(InverterType::Hybrid);
+        assert!(
+            ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| {
+                e == Error::invalid_graph(concat!(
+                    "Battery:5 can't be shared by inverters of different types. ",
+                    "Found BatteryInverter:3 and HybridInverter:4."
+                )) && e.rule() == Some("validate_batteries::shared_inverter_types")
+            }),
+        );
+
+        // Inverters sharing a battery must be under the same meter.
+        components[3].1 = ComponentCategory::Inverter(InverterType::Battery);
+        components.push(TestComponent(6, ComponentCategory::Meter));
+        connections[2] = TestConnection::new(1, 6);
+        connections.push(TestConnection::new(6, 4));
+        assert!(
+            ComponentGraph::try_new(components, connections).is_err_and(|e| {
+                e == Error::invalid_graph(concat!(
+                    "Battery:5 can't be shared by inverters with different predecessors. ",
+                    "Found BatteryInverter:3 and BatteryInverter:4."
+                )) && e.rule() == Some("validate_batteries::shared_inverter_predecessors")
+            }),
+        );
+    }
+
     #[test]
     fn test_validate_ev_chargers() {
         let mut components = vec![
             TestComponent(1, ComponentCategory::Grid),
             TestComponent(2, ComponentCategory::Meter),
-            TestComponent(3, ComponentCategory::EvCharger),
+            TestComponent(3, ComponentCategory::EvCharger(EvChargerType::Unspecified)),
             TestComponent(4, ComponentCategory::Electrolyzer),
         ];
         let mut connections = vec![
@@ -377,6 +792,296 @@ mod tests {
         assert!(ComponentGraph::try_new(components, connections).is_ok());
     }
 
+    #[test]
+    fn test_validate_ev_charger_types() {
+        let mut components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Meter),
+            TestComponent(3, ComponentCategory::EvCharger(EvChargerType::Dc)),
+            TestComponent(4, ComponentCategory::EvCharger(EvChargerType::Ac)),
+        ];
+        let mut connections = vec![
+            TestConnection::new(1, 2),
+            TestConnection::new(2, 3),
+            TestConnection::new(2, 4),
+        ];
+        let config = ComponentGraphConfig {
+            validate_ev_charger_types: true,
+            ..Default::default()
+        };
+
+        assert!(ComponentGraph::try_new(components.clone(), connections.clone()).is_ok());
+        assert!(ComponentGraph::try_new_with_config(
+            components.clone(),
+            connections.clone(),
+            config.clone()
+        )
+        .is_err_and(|e| {
+            e == Error::invalid_graph(
+                "DCEVCharger:3 can't have predecessors with multiple successors. Found Meter:2.",
+            )
+        }));
+
+        // AC EV chargers can share a meter.
+        components[2].1 = ComponentCategory::EvCharger(EvChargerType::Ac);
+        assert!(ComponentGraph::try_new_with_config(
+            components.clone(),
+            connections.clone(),
+            config.clone()
+        )
+        .is_ok());
+
+        // DC EV chargers need a meter of their own.
+        components[2].1 = ComponentCategory::EvCharger(EvChargerType::Dc);
+        connections[1] = TestConnection::new(1, 3);
+        assert!(ComponentGraph::try_new_with_config(
+            components.clone(),
+            connections.clone(),
+            config.clone()
+        )
+        .is_err_and(|e| {
+            e == Error::invalid_graph(
+                "DCEVCharger:3 can only have predecessors with categories: [Meter]. Found Grid:1.",
+            )
+        }));
+
+        components.push(TestComponent(5, ComponentCategory::Meter));
+        connections[1] = TestConnection::new(1, 5);
+        connections.push(TestConnection::new(5, 3));
+        assert!(ComponentGraph::try_new_with_config(components, connections, config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_successor_rules() {
+        let mut components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Meter),
+            TestComponent(3, ComponentCategory::EvCharger(EvChargerType::Ac)),
+            TestComponent(4, ComponentCategory::Meter),
+        ];
+        let mut connections = vec![
+            TestConnection::new(1, 2),
+            TestConnection::new(2, 3),
+            TestConnection::new(3, 4),
+        ];
+        let config = ComponentGraphConfig {
+            successor_rules: [(
+                ComponentCategory::EvCharger(EvChargerType::Ac),
+                vec![ComponentCategory::Meter],
+            )]
+            .into(),
+            ..Default::default()
+        };
+
+        assert!(validate_components(
+            components.clone(),
+            connections.clone(),
+            ComponentGraphConfig::default()
+        )
+        .errors()
+        .contains(&Error::invalid_graph(
+            "ACEVCharger:3 can't have any successors. Found Meter:4."
+        )));
+        // Submeters are allowed under AC EV chargers with the config.
+        assert!(ComponentGraph::try_new_with_config(
+            components.clone(),
+            connections.clone(),
+            config.clone()
+        )
+        .is_ok());
+
+        // Other successors are still not allowed.
+        components[3].1 = ComponentCategory::Chp;
+        assert!(
+            validate_components(components.clone(), connections.clone(), config.clone())
+                .errors()
+                .contains(&Error::invalid_graph(
+                    "ACEVCharger:3 can only have successors with categories [Meter]. Found CHP:4."
+                ))
+        );
+
+        // And DC EV chargers still have to be leaves.
+        components[2].1 = ComponentCategory::EvCharger(EvChargerType::Dc);
+        components[3].1 = ComponentCategory::Meter;
+        assert!(
+            validate_components(components.clone(), connections.clone(), config)
+                .errors()
+                .contains(&Error::invalid_graph(
+                    "DCEVCharger:3 can't have any successors. Found Meter:4."
+                ))
+        );
+
+        // An empty list makes components leaves.
+        components[2].1 = ComponentCategory::Inverter(InverterType::Battery);
+        components[3].1 = ComponentCategory::Battery;
+        let config = ComponentGraphConfig {
+            successor_rules: [(ComponentCategory::Inverter(InverterType::Battery), vec![])].into(),
+            ..Default::default()
+        };
+        assert!(ComponentGraph::try_new(components.clone(), connections.clone()).is_ok());
+        assert!(ComponentGraph::try_new_with_config(
+            components.clone(),
+            connections.clone(),
+            config.clone()
+        )
+        .is_err_and(|e| {
+            e == Error::invalid_graph(
+                "BatteryInverter:3 can't have any successors. Found Battery:4.",
+            )
+        }));
+
+        // Battery inverters without batteries are then accepted.
+        components.pop();
+        connections.pop();
+        assert!(ComponentGraph::try_new(components.clone(), connections.clone()).is_err());
+        assert!(ComponentGraph::try_new_with_config(components, connections, config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_assumptions() -> Result<(), Error> {
+        let components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Meter),
+            TestComponent(3, ComponentCategory::Inverter(InverterType::Unspecified)),
+            TestComponent(4, ComponentCategory::Battery),
+            TestComponent(5, ComponentCategory::Other(42)),
+        ];
+        let connections = vec![
+            TestConnection::new(1, 2),
+            TestConnection::new(2, 3),
+            TestConnection::new(3, 4),
+            TestConnection::new(2, 5),
+        ];
+        let config = ComponentGraphConfig {
+            allow_unspecified_inverters: true,
+            other_categories: OtherCategoryPolicy::PassThrough,
+            ..Default::default()
+        };
+
+        let graph = ComponentGraph::try_new_with_config(components, connections, config)?;
+        let report = graph.revalidate();
+        assert!(report.is_valid());
+        assert_eq!(
+            report.warnings(),
+            &[
+                Error::assumption("UnspecifiedInverter:3 is treated as a BatteryInverter."),
+                Error::assumption(concat!(
+                    "Other(42):5 has a category unknown to this library, ",
+                    "and is only partly validated."
+                )),
+            ]
+        );
+        assert_eq!(
+            report.warnings()[0].rule(),
+            Some("validate_assumptions::unspecified_inverter_type")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_other_categories() {
+        let mut components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Meter),
+            TestComponent(3, ComponentCategory::Other(42)),
+        ];
+        let mut connections = vec![TestConnection::new(1, 2), TestConnection::new(2, 3)];
+        let config = |other_categories| ComponentGraphConfig {
+            other_categories,
+            ..Default::default()
+        };
+
+        assert!(ComponentGraph::try_new(components.clone(), connections.clone()).is_err());
+        for policy in [
+            OtherCategoryPolicy::PassThrough,
+            OtherCategoryPolicy::LeafOnly,
+        ] {
+            assert!(ComponentGraph::try_new_with_config(
+                components.clone(),
+                connections.clone(),
+                config(policy)
+            )
+            .is_ok());
+        }
+
+        components.push(TestComponent(4, ComponentCategory::Hvac));
+        connections.push(TestConnection::new(3, 4));
+        assert!(ComponentGraph::try_new_with_config(
+            components.clone(),
+            connections.clone(),
+            config(OtherCategoryPolicy::PassThrough)
+        )
+        .is_ok());
+        assert!(ComponentGraph::try_new_with_config(
+            components.clone(),
+            connections.clone(),
+            config(OtherCategoryPolicy::LeafOnly)
+        )
+        .is_err_and(|e| {
+            e == Error::invalid_graph("Other(42):3 can't have any successors. Found HVAC:4.")
+                && e.rule() == Some("validate_other_categories::leaf")
+        }));
+
+        // The rules of the neighbors still apply with `PassThrough`.
+        components.push(TestComponent(
+            5,
+            ComponentCategory::Inverter(InverterType::Solar),
+        ));
+        connections.push(TestConnection::new(3, 5));
+        assert!(ComponentGraph::try_new_with_config(
+            components,
+            connections,
+            config(OtherCategoryPolicy::PassThrough)
+        )
+        .is_err_and(|e| e
+            == Error::invalid_graph(concat!(
+                "SolarInverter:5 can only have predecessors with categories: ",
+                "[Meter, Grid, Fuse]. Found Other(42):3."
+            ))));
+    }
+
+    #[test]
+    fn test_validate_sensors() {
+        let mut components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Meter),
+            TestComponent(3, ComponentCategory::Sensor),
+            TestComponent(4, ComponentCategory::Sensor),
+            TestComponent(5, ComponentCategory::Inverter(InverterType::Solar)),
+            TestComponent(6, ComponentCategory::Sensor),
+        ];
+        let mut connections = vec![
+            TestConnection::new(1, 2),
+            TestConnection::new(1, 3),
+            TestConnection::new(2, 4),
+            TestConnection::new(2, 5),
+            TestConnection::new(5, 6),
+        ];
+        assert!(
+            ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| {
+                e == Error::invalid_graph(
+                    "SolarInverter:5 can only have successors with categories [PVString]. Found Sensor:6.",
+                )
+            }),
+        );
+
+        components.pop();
+        connections.pop();
+        assert!(ComponentGraph::try_new(components.clone(), connections.clone()).is_ok());
+
+        components.push(TestComponent(6, ComponentCategory::Meter));
+        connections.push(TestConnection::new(4, 6));
+        assert!(
+            ComponentGraph::try_new(components, connections).is_err_and(|e| {
+                e == Error::invalid_graph(
+                    "Meter:6 can only have predecessors with categories: \
+                     [Grid, Meter, Fuse]. Found Sensor:4.",
+                )
+            }),
+        );
+    }
+
     #[test]
     fn test_validate_chps() {
         let mut components = vec![
@@ -401,4 +1106,79 @@ mod tests {
 
         assert!(ComponentGraph::try_new(components, connections).is_ok());
     }
+
+    #[test]
+    fn test_validate_fuses() -> Result<(), Error> {
+        let mut components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Fuse),
+            TestComponent(3, ComponentCategory::Meter),
+            TestComponent(4, ComponentCategory::Inverter(InverterType::Solar)),
+        ];
+        let mut connections = vec![
+            TestConnection::new(1, 2),
+            TestConnection::new(2, 3),
+            TestConnection::new(3, 4),
+        ];
+        let graph = ComponentGraph::try_new(components.clone(), connections.clone())?;
+        assert_eq!(graph.grid_fuse().map(|n| n.component_id()), Some(2));
+        assert!(graph.is_grid_meter(3)?);
+
+        // A branch fuse under a meter is not the grid fuse, and is accepted.
+        components.push(TestComponent(5, ComponentCategory::Fuse));
+        components.push(TestComponent(6, ComponentCategory::Meter));
+        connections.push(TestConnection::new(3, 5));
+        connections.push(TestConnection::new(5, 6));
+        let graph = ComponentGraph::try_new(components.clone(), connections.clone())?;
+        assert_eq!(graph.grid_fuse().map(|n| n.component_id()), Some(2));
+        assert!(!graph.is_grid_meter(6)?);
+
+        // The grid fuse can't also sit under a meter.
+        components.push(TestComponent(7, ComponentCategory::Meter));
+        connections.push(TestConnection::new(1, 7));
+        connections.push(TestConnection::new(7, 2));
+        assert!(validate_components(
+            components.clone(),
+            connections.clone(),
+            ComponentGraphConfig::default()
+        )
+        .errors()
+        .iter()
+        .any(|e| {
+            e == &Error::invalid_graph(
+                "Fuse:2 can only have predecessors with categories: [Grid]. Found Meter:7.",
+            ) && e.rule() == Some("validate_fuses::predecessor_categories")
+        }));
+
+        // There can only be one fuse under the grid.
+        components = vec![
+            TestComponent(1, ComponentCategory::Grid),
+            TestComponent(2, ComponentCategory::Fuse),
+            TestComponent(3, ComponentCategory::Meter),
+            TestComponent(4, ComponentCategory::Fuse),
+            TestComponent(5, ComponentCategory::Meter),
+        ];
+        connections = vec![
+            TestConnection::new(1, 2),
+            TestConnection::new(2, 3),
+            TestConnection::new(1, 4),
+            TestConnection::new(4, 5),
+        ];
+        assert!(
+            ComponentGraph::try_new(components.clone(), connections.clone()).is_err_and(|e| {
+                e == Error::invalid_graph(
+                    "Grid:1 can only have one fuse as a successor. Found Fuse:2 and Fuse:4.",
+                ) && e.rule() == Some("validate_fuses::single_grid_fuse")
+            })
+        );
+
+        components.truncate(2);
+        connections.truncate(1);
+        components.push(TestComponent(3, ComponentCategory::Meter));
+        connections.push(TestConnection::new(1, 3));
+        let graph = ComponentGraph::try_new(components, connections)?;
+        assert_eq!(graph.grid_fuse().map(|n| n.component_id()), Some(2));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,145 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for checking that single-phase components in a [`ComponentGraph`]
+//! are balanced across the phases.
+
+use std::collections::BTreeMap;
+
+use crate::{component_category::CategoryPredicates, Edge, Error, Node, Phase, Phases};
+
+use super::ComponentGraphValidator;
+
+impl<N, E> ComponentGraphValidator<'_, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Checks that the single-phase EV chargers beneath each component are
+    /// spread evenly across the phases.
+    ///
+    /// The chargers are unbalanced when the numbers on any two phases differ
+    /// by more than one.  Like the rating checks, the problems found are
+    /// returned as warnings.
+    pub(super) fn validate_phase_balance(&self) -> Vec<Error> {
+        let mut chargers = BTreeMap::<u64, BTreeMap<Phase, usize>>::new();
+        for (source, connection, destination) in self.cg.connections_with_nodes() {
+            if let (true, Some(Phases::Single(phase))) =
+                (destination.is_ev_charger(), connection.phases())
+            {
+                *chargers
+                    .entry(source.component_id())
+                    .or_default()
+                    .entry(phase)
+                    .or_default() += 1;
+            }
+        }
+
+        let mut warnings = vec![];
+        for (cid, counts) in chargers {
+            let counts = Phase::ALL.map(|p| (p, counts.get(&p).copied().unwrap_or_default()));
+            let min = counts.iter().map(|(_, n)| *n).min().unwrap_or_default();
+            let max = counts.iter().map(|(_, n)| *n).max().unwrap_or_default();
+            if max - min <= 1 {
+                continue;
+            }
+            let Ok(node) = self.cg.component(cid) else {
+                continue;
+            };
+            warnings.push(
+                Error::phase_imbalance(format!(
+                    "{}:{} has single-phase EV chargers unevenly spread across phases: {}.",
+                    node.category(),
+                    cid,
+                    counts
+                        .iter()
+                        .map(|(phase, n)| format!("{phase}: {n}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+                .with_components([cid])
+                .with_rule("ev_charger_phases"),
+            );
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ComponentCategory, ComponentGraph, EvChargerType, SimpleComponent, SimpleConnection,
+    };
+
+    #[test]
+    fn test_validate_phase_balance() -> Result<(), Error> {
+        let mut components = vec![
+            SimpleComponent::new(1, ComponentCategory::Grid),
+            SimpleComponent::new(2, ComponentCategory::Meter),
+        ];
+        let mut connections = vec![SimpleConnection::new(1, 2)];
+        for (id, phase) in [
+            (3, Phase::L1),
+            (4, Phase::L2),
+            (5, Phase::L3),
+            (6, Phase::L1),
+        ] {
+            components.push(SimpleComponent::new(
+                id,
+                ComponentCategory::EvCharger(EvChargerType::Ac),
+            ));
+            connections.push(SimpleConnection::new(2, id).with_phases(Phases::Single(phase)));
+        }
+
+        let graph = ComponentGraph::try_new(components.clone(), connections.clone())?;
+        assert!(graph.revalidate().warnings().is_empty());
+        assert_eq!(
+            graph
+                .successors_on_phase(2, Phase::L1)?
+                .map(|n| n.id)
+                .collect::<Vec<_>>(),
+            vec![3, 6]
+        );
+
+        // A third charger on L1, with none added on L2 and L3.
+        components.push(SimpleComponent::new(
+            7,
+            ComponentCategory::EvCharger(EvChargerType::Ac),
+        ));
+        connections.push(SimpleConnection::new(2, 7).with_phases(Phases::Single(Phase::L1)));
+        // Three-phase chargers don't count.
+        components.push(SimpleComponent::new(
+            8,
+            ComponentCategory::EvCharger(EvChargerType::Ac),
+        ));
+        connections.push(SimpleConnection::new(2, 8).with_phases(Phases::Three));
+
+        let graph = ComponentGraph::try_new(components, connections)?;
+        let report = graph.revalidate();
+        assert!(report.is_valid());
+        assert_eq!(
+            report.warnings(),
+            &[Error::phase_imbalance(concat!(
+                "Meter:2 has single-phase EV chargers unevenly spread across phases: ",
+                "L1: 3, L2: 1, L3: 1."
+            ))]
+        );
+        assert_eq!(
+            report.warnings()[0].rule(),
+            Some("validate_phase_balance::ev_charger_phases")
+        );
+        assert_eq!(
+            graph
+                .successors_on_phase(2, Phase::L2)?
+                .map(|n| n.id)
+                .collect::<Vec<_>>(),
+            vec![4, 8]
+        );
+        assert!(graph
+            .successors_on_phase(9, Phase::L2)
+            .is_err_and(|e| e == Error::component_not_found("Component with id 9 not found.")));
+
+        Ok(())
+    }
+}
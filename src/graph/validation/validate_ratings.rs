@@ -0,0 +1,215 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for checking that the rated powers of the components in a
+//! [`ComponentGraph`], and the rated currents of its connections, are
+//! consistent.
+
+use crate::{component_category::CategoryPredicates, Edge, Error, Node};
+
+use super::ComponentGraphValidator;
+
+impl<N, E> ComponentGraphValidator<'_, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Checks that the combined rated power of the successors of the grid and
+    /// of each meter doesn't exceed their own rated power.
+    ///
+    /// Successors without a known rated power are not counted.  The problems
+    /// found are returned as warnings, because they don't prevent the graph
+    /// from being used.
+    pub(super) fn validate_rated_power(&self) -> Vec<Error> {
+        let mut warnings = vec![];
+        for node in self.cg.components().filter(|n| n.is_grid() || n.is_meter()) {
+            let Some(rated_power) = node.rated_power() else {
+                continue;
+            };
+            let Ok(successors) = self.cg.successors(node.component_id()) else {
+                continue;
+            };
+            let successor_ratings = successors.filter_map(|n| n.rated_power()).sum::<f64>();
+            if successor_ratings > rated_power {
+                warnings.push(Error::rated_power_exceeded(format!(
+                    "{}:{} has a rated power of {} W, but its successors have a combined rated power of {} W.",
+                    node.category(),
+                    node.component_id(),
+                    rated_power,
+                    successor_ratings
+                ))
+                .with_components([node.component_id()])
+                .with_rule("rated_power"));
+            }
+        }
+        warnings
+    }
+
+    /// Checks that the destination of each connection isn't rated for more
+    /// current than the connection itself.
+    ///
+    /// Connections and components without a known rated current are not
+    /// checked.  Like [`validate_rated_power`][Self::validate_rated_power],
+    /// the problems found are returned as warnings.
+    pub(super) fn validate_connection_ratings(&self) -> Vec<Error> {
+        let mut warnings = vec![];
+        for (source, connection, destination) in self.cg.connections_with_nodes() {
+            let (Some(connection_current), Some(destination_current)) =
+                (connection.rated_current(), destination.rated_current())
+            else {
+                continue;
+            };
+            if destination_current > connection_current {
+                warnings.push(
+                    Error::rated_current_exceeded(format!(
+                        "{}:{} has a rated current of {} A, but the connection from {}:{} is rated for {} A.",
+                        destination.category(),
+                        destination.component_id(),
+                        destination_current,
+                        source.category(),
+                        source.component_id(),
+                        connection_current
+                    ))
+                    .with_components([destination.component_id(), source.component_id()])
+                    .with_connection(source.component_id(), destination.component_id())
+                    .with_rule("connection_rated_current"),
+                );
+            }
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ComponentCategory;
+    use crate::ComponentGraph;
+    use crate::InverterType;
+    use crate::SimpleConnection;
+
+    #[derive(Clone)]
+    struct TestComponent(u64, ComponentCategory, Option<f64>, Option<f64>);
+
+    impl Node for TestComponent {
+        fn component_id(&self) -> u64 {
+            self.0
+        }
+
+        fn category(&self) -> ComponentCategory {
+            self.1
+        }
+
+        fn is_supported(&self) -> bool {
+            true
+        }
+
+        fn rated_power(&self) -> Option<f64> {
+            self.2
+        }
+
+        fn rated_current(&self) -> Option<f64> {
+            self.3
+        }
+    }
+
+    #[test]
+    fn test_validate_rated_power() -> Result<(), Error> {
+        let mut components = vec![
+            TestComponent(1, ComponentCategory::Grid, Some(50_000.0), None),
+            TestComponent(2, ComponentCategory::Meter, Some(30_000.0), None),
+            TestComponent(
+                3,
+                ComponentCategory::Inverter(InverterType::Solar),
+                Some(20_000.0),
+                None,
+            ),
+            TestComponent(
+                4,
+                ComponentCategory::Inverter(InverterType::Solar),
+                Some(10_000.0),
+                None,
+            ),
+            TestComponent(5, ComponentCategory::Meter, None, None),
+            TestComponent(6, ComponentCategory::Chp, Some(40_000.0), None),
+        ];
+        let connections = vec![
+            SimpleConnection::new(1, 2),
+            SimpleConnection::new(2, 3),
+            SimpleConnection::new(2, 4),
+            SimpleConnection::new(1, 5),
+            SimpleConnection::new(5, 6),
+        ];
+
+        let graph = ComponentGraph::try_new(components.clone(), connections.clone())?;
+        assert_eq!(graph.revalidate().warnings(), &[]);
+
+        components[3].2 = Some(15_000.0);
+        components[4].2 = Some(45_000.0);
+
+        let graph = ComponentGraph::try_new(components, connections)?;
+        let report = graph.revalidate();
+        assert!(report.is_valid());
+        assert_eq!(
+            report.warnings(),
+            &[
+                Error::rated_power_exceeded(concat!(
+                    "Grid:1 has a rated power of 50000 W, but its successors ",
+                    "have a combined rated power of 75000 W."
+                )),
+                Error::rated_power_exceeded(concat!(
+                    "Meter:2 has a rated power of 30000 W, but its successors ",
+                    "have a combined rated power of 35000 W."
+                )),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_connection_ratings() -> Result<(), Error> {
+        let components = vec![
+            TestComponent(1, ComponentCategory::Grid, None, None),
+            TestComponent(2, ComponentCategory::Meter, None, Some(63.0)),
+            TestComponent(
+                3,
+                ComponentCategory::Inverter(InverterType::Solar),
+                None,
+                Some(32.0),
+            ),
+            TestComponent(4, ComponentCategory::Chp, None, Some(40.0)),
+        ];
+        let mut connections = vec![
+            SimpleConnection::new(1, 2).with_rated_current(80.0),
+            SimpleConnection::new(2, 3).with_rated_current(32.0),
+            SimpleConnection::new(2, 4),
+        ];
+
+        let graph = ComponentGraph::try_new(components.clone(), connections.clone())?;
+        assert!(graph.revalidate().warnings().is_empty());
+
+        connections[1] = SimpleConnection::new(2, 3)
+            .with_rated_current(25.0)
+            .with_cable_length(120.0);
+        connections[2] = SimpleConnection::new(2, 4).with_rated_current(40.0);
+
+        let graph = ComponentGraph::try_new(components, connections)?;
+        let report = graph.revalidate();
+        assert!(report.is_valid());
+        assert_eq!(
+            report.warnings(),
+            &[Error::rated_current_exceeded(concat!(
+                "SolarInverter:3 has a rated current of 32 A, ",
+                "but the connection from Meter:2 is rated for 25 A."
+            ))]
+        );
+        assert_eq!(report.warnings()[0].connection(), Some((2, 3)));
+        assert_eq!(
+            report.warnings()[0].rule(),
+            Some("validate_connection_ratings::connection_rated_current")
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Methods for grouping the components of a [`ComponentGraph`] into zones of
+//! the same voltage level, separated by voltage transformers.
+
+use std::collections::{hash_map::Entry, BTreeMap, HashMap, VecDeque};
+
+use petgraph::graph::NodeIndex;
+
+use crate::{ComponentCategory, ComponentGraph, Edge, Error, Node};
+
+/// Voltage zones.
+impl<N, E> ComponentGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns the voltage zone of the component with the given
+    /// `component_id`.
+    ///
+    /// A zone is identified by the component at its top: the grid for the
+    /// zone of the grid connection, or the voltage transformer that feeds
+    /// it.  Transformers themselves belong to the zone of their primary
+    /// side.  Components reachable from multiple zones belong to the zone
+    /// of their shortest path from the grid.
+    ///
+    /// Each call walks the whole graph, so for the zones of many components,
+    /// use [`voltage_zones`][Self::voltage_zones] instead.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn voltage_zone(&self, component_id: u64) -> Result<u64, Error> {
+        self.component(component_id)?;
+        let index = self.node_indices[&component_id];
+        Ok(self.zones_by_index()[&index])
+    }
+
+    /// Returns the voltage zone of every component, as returned by
+    /// [`voltage_zone`][Self::voltage_zone], by component ID.
+    ///
+    /// The zones of all components are found with a single walk of the graph.
+    pub fn voltage_zones(&self) -> BTreeMap<u64, u64> {
+        self.zones_by_index()
+            .into_iter()
+            .map(|(index, zone)| (self.graph[index].component_id(), zone))
+            .collect()
+    }
+
+    /// Returns the components in the given voltage `zone`, as returned by
+    /// [`voltage_zone`][Self::voltage_zone], in the order they were given
+    /// when the graph was created.
+    ///
+    /// Returns an error if the `zone` is not the ID of the grid or of a
+    /// voltage transformer.
+    pub fn components_in_zone(&self, zone: u64) -> Result<Vec<&N>, Error> {
+        let top = self.component(zone)?;
+        if !matches!(
+            top.category(),
+            ComponentCategory::Grid | ComponentCategory::VoltageTransformer
+        ) {
+            return Err(Error::invalid_component(format!(
+                "Component {zone} is not the grid or a voltage transformer."
+            ))
+            .with_components([zone]));
+        }
+        let zones = self.zones_by_index();
+        Ok(self
+            .graph
+            .node_indices()
+            .filter(|index| zones[index] == zone)
+            .map(|index| &self.graph[index])
+            .collect())
+    }
+
+    /// Returns the nominal voltage of the given `zone` in volts, from the
+    /// [`secondary_voltage`][Node::secondary_voltage] of the transformer
+    /// that feeds it, if known.
+    ///
+    /// The voltage of the zone of the grid connection is not known.
+    ///
+    /// Returns an error if the `zone` does not exist.
+    pub fn zone_voltage(&self, zone: u64) -> Result<Option<f64>, Error> {
+        let top = self.component(zone)?;
+        Ok(match top.category() {
+            ComponentCategory::VoltageTransformer => top.secondary_voltage(),
+            _ => None,
+        })
+    }
+
+    /// Returns the voltage zone of each component, walking down from the
+    /// grid.
+    fn zones_by_index(&self) -> HashMap<NodeIndex, u64> {
+        let root = self.node_indices[&self.root_id];
+        let mut zones = HashMap::from([(root, self.root_id)]);
+        let mut queue = VecDeque::from([root]);
+        while let Some(index) = queue.pop_front() {
+            let node = &self.graph[index];
+            let zone = match node.category() {
+                ComponentCategory::VoltageTransformer => node.component_id(),
+                _ => zones[&index],
+            };
+            for &successor in &self.adjacency[index.index()].successors {
+                if let Entry::Vacant(entry) = zones.entry(successor) {
+                    entry.insert(zone);
+                    queue.push_back(successor);
+                }
+            }
+        }
+        zones
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentGraphConfig, InverterType, SimpleConnection};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct TestComponent(u64, ComponentCategory, Option<f64>);
+
+    impl Node for TestComponent {
+        fn component_id(&self) -> u64 {
+            self.0
+        }
+
+        fn category(&self) -> ComponentCategory {
+            self.1
+        }
+
+        fn is_supported(&self) -> bool {
+            true
+        }
+
+        fn secondary_voltage(&self) -> Option<f64> {
+            self.2
+        }
+    }
+
+    #[test]
+    fn test_voltage_zones() -> Result<(), Error> {
+        let graph = ComponentGraph::try_new_with_config(
+            vec![
+                TestComponent(1, ComponentCategory::Grid, None),
+                TestComponent(2, ComponentCategory::Meter, None),
+                TestComponent(3, ComponentCategory::VoltageTransformer, Some(400.0)),
+                TestComponent(4, ComponentCategory::Inverter(InverterType::Solar), None),
+                TestComponent(5, ComponentCategory::Inverter(InverterType::Solar), None),
+                TestComponent(6, ComponentCategory::Meter, None),
+                TestComponent(7, ComponentCategory::Chp, None),
+            ],
+            vec![
+                SimpleConnection::new(1, 2),
+                SimpleConnection::new(2, 3),
+                SimpleConnection::new(3, 4),
+                SimpleConnection::new(3, 5),
+                SimpleConnection::new(2, 6),
+                SimpleConnection::new(6, 7),
+            ],
+            ComponentGraphConfig {
+                transparent_voltage_transformers: true,
+                ..Default::default()
+            },
+        )?;
+
+        let zones = (1..=7)
+            .map(|id| graph.voltage_zone(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(zones, [1, 1, 1, 3, 3, 1, 1]);
+        assert_eq!(
+            graph.voltage_zones(),
+            BTreeMap::from([(1, 1), (2, 1), (3, 1), (4, 3), (5, 3), (6, 1), (7, 1)])
+        );
+
+        let ids = |zone| -> Result<Vec<u64>, Error> {
+            Ok(graph
+                .components_in_zone(zone)?
+                .into_iter()
+                .map(|n| n.component_id())
+                .collect())
+        };
+        assert_eq!(ids(1)?, [1, 2, 3, 6, 7]);
+        assert_eq!(ids(3)?, [4, 5]);
+        assert_eq!(graph.zone_voltage(3)?, Some(400.0));
+        assert_eq!(graph.zone_voltage(1)?, None);
+
+        assert_eq!(
+            graph.components_in_zone(4),
+            Err(Error::invalid_component(
+                "Component 4 is not the grid or a voltage transformer."
+            ))
+        );
+        assert_eq!(
+            graph.voltage_zone(9),
+            Err(Error::component_not_found("Component with id 9 not found."))
+        );
+
+        Ok(())
+    }
+}
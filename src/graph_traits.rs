@@ -5,6 +5,7 @@
 //! that represent a node and an edge.
 
 use crate::component_category::ComponentCategory;
+use crate::Phases;
 
 /**
 This trait needs to be implemented by the type that represents a node.
@@ -55,7 +56,21 @@ impl component_graph::Node for common::v1::microgrid::components::Component {
             }
             pb::ComponentCategory::Converter => gr::ComponentCategory::Converter,
             pb::ComponentCategory::Battery => gr::ComponentCategory::Battery,
-            pb::ComponentCategory::EvCharger => gr::ComponentCategory::EvCharger,
+            pb::ComponentCategory::EvCharger => {
+                gr::ComponentCategory::EvCharger(match self.category_type {
+                    Some(pb::ComponentCategoryMetadataVariant {
+                        metadata: Some(pb::component_category_metadata_variant::Metadata::EvCharger(
+                            ev_charger,
+                        )),
+                    }) => match pb::EvChargerType::try_from(ev_charger.r#type) {
+                        Ok(pb::EvChargerType::Ac) => gr::EvChargerType::Ac,
+                        Ok(pb::EvChargerType::Dc) => gr::EvChargerType::Dc,
+                        Ok(pb::EvChargerType::Hybrid) => gr::EvChargerType::Hybrid,
+                        _ => gr::EvChargerType::Unspecified,
+                    },
+                    _ => gr::EvChargerType::Unspecified,
+                })
+            }
             pb::ComponentCategory::CryptoMiner => gr::ComponentCategory::CryptoMiner,
             pb::ComponentCategory::Electrolyzer => gr::ComponentCategory::Electrolyzer,
             pb::ComponentCategory::Chp => gr::ComponentCategory::Chp,
@@ -82,6 +97,46 @@ pub trait Node {
     fn category(&self) -> ComponentCategory;
     /// Returns true if the component can be read from and/or controlled.
     fn is_supported(&self) -> bool;
+    /// Returns the rated power of the component in watts, if known.
+    ///
+    /// Used for checking that the ratings of connected components are
+    /// consistent.  The default implementation returns `None`.
+    fn rated_power(&self) -> Option<f64> {
+        None
+    }
+    /// Returns the rated current of the component in amperes, if known.
+    ///
+    /// For the grid fuse, this is the limit of the grid connection, available
+    /// from [`ComponentGraph::grid_fuse`][crate::ComponentGraph::grid_fuse].
+    /// The default implementation returns `None`.
+    fn rated_current(&self) -> Option<f64> {
+        None
+    }
+    /// Returns the nominal voltage on the secondary side of the component in
+    /// volts, if known.
+    ///
+    /// Only used for voltage transformers, to tell the voltage level of the
+    /// zone beneath them.  The default implementation returns `None`.
+    fn secondary_voltage(&self) -> Option<f64> {
+        None
+    }
+    /// Returns a human readable name of the component, if it has one.
+    ///
+    /// Only used when exporting the graph, for example with
+    /// [`ComponentGraph::write_csv`][crate::ComponentGraph::write_csv].  The
+    /// default implementation returns `None`.
+    fn name(&self) -> Option<&str> {
+        None
+    }
+    /// Returns an external identifier of the component, like a UUID from the
+    /// cloud APIs, if it has one.
+    ///
+    /// Components can be looked up by these with
+    /// [`ComponentGraph::component_by_external_id`][crate::ComponentGraph::component_by_external_id].
+    /// The default implementation returns `None`.
+    fn external_id(&self) -> Option<&str> {
+        None
+    }
 }
 
 /**
@@ -111,4 +166,30 @@ pub trait Edge {
     fn source(&self) -> u64;
     /// Returns the destination component id of the connection.
     fn destination(&self) -> u64;
+    /// Returns the rated current of the connection in amperes, like that of
+    /// its cable, if known.
+    ///
+    /// Used for warning about components that are rated for more current
+    /// than the connection to them.  The default implementation returns
+    /// `None`.
+    fn rated_current(&self) -> Option<f64> {
+        None
+    }
+    /// Returns the length of the cable of the connection in meters, if
+    /// known.
+    ///
+    /// Only carried over to exports.  The default implementation returns
+    /// `None`.
+    fn cable_length(&self) -> Option<f64> {
+        None
+    }
+    /// Returns the phases carried by the connection, if known.
+    ///
+    /// Connections with unknown phases are treated as three-phase, for
+    /// example by
+    /// [`ComponentGraph::successors_on_phase`][crate::ComponentGraph::successors_on_phase].
+    /// The default implementation returns `None`.
+    fn phases(&self) -> Option<Phases> {
+        None
+    }
 }
@@ -25,6 +25,15 @@ Therefore, to be usable with this library, the component and connection types
 must implement the [`Node`] and [`Edge`] traits, respectively.  Check out the
 documentation for these traits for sample implementations.
 
+For tools and tests that don't have their own types, the library provides the
+[`SimpleComponent`] and [`SimpleConnection`] types, which implement these
+traits.  Graphs of these types can also be put together programmatically,
+for example in tests, with a [`ComponentGraphBuilder`][builder::ComponentGraphBuilder].
+With the `test-support` feature, the `random` module generates random valid
+topologies, for property testing code that works with component graphs.  The
+`proptest` feature builds on it, with `proptest` strategies in the
+`strategies` module.
+
 ## Validation
 
 The [`try_new`][ComponentGraph::try_new] method several checks on the graph
@@ -39,16 +48,117 @@ including checking that:
 
 If any of the validation steps fail, the method will return an [`Error`], and a
 [`ComponentGraph`] instance otherwise.
+
+To run the same checks without keeping the graph, for example to lint a site
+configuration, use [`validate_components`], which returns a
+[`ValidationReport`] listing all the failures that were found.
+
+For ingesting data that can't always be fixed at the source,
+[`try_new_partial`][ComponentGraph::try_new_partial] creates a graph without
+the components and connections that would make it invalid, and returns them
+in a [`Quarantine`] with the reasons.
+
+The individual checks are available in the [`invariants`] module, for
+writing site-specific rules with the same errors as the built-in ones.
+
+The checks can be adjusted for the needs of a site with a
+[`ComponentGraphConfig`], which can be loaded from JSON, TOML, etc. with the
+`serde` feature.
+
+With the `tracing` feature, graph creation and validation run in `tracing`
+spans, that record the size of the graph and the time the steps took.
+
+With the `miette` feature, [`Error`] implements `miette::Diagnostic`, with
+labels pointing at the offending components.
+
+With the `serde` feature, graphs can also be exported to and imported from
+JSON with `ComponentGraph::to_json` and `ComponentGraph::from_json`, using the
+built-in [`SimpleComponent`] and [`SimpleConnection`] types.  Similarly, the
+`csv` feature adds `ComponentGraph::write_csv` and `ComponentGraph::from_csv`,
+for site definitions that are maintained in spreadsheets.
+
+With the `binary` feature, validated graphs can be saved with
+`ComponentGraph::to_bytes` and restored with `ComponentGraph::from_bytes`,
+without being validated again, to speed up the start of services that manage
+many sites.
+
+With the `python` feature, the library can be built as a Python extension
+module named `component_graph`, for example with `maturin`.  It exposes a
+`ComponentGraph` class that is created from the JSON representation of a
+graph, and raises `ComponentGraphError` exceptions on failures.
+
+A [`ComponentGraph`] is `Send` and `Sync` when its node and edge types are,
+so it can be shared between threads in an `Arc`.  Services that need to
+replace the graph while it is being read can keep it in a [`Snapshot`],
+which can also notify them of the changes, for invalidating their caches.
+Single-owner code can keep it in a [`VersionedGraph`] instead, whose version
+tells caches cheaply whether they are stale.
+
+Site-level information, like the microgrid ID, name, location and time zone,
+can be attached to a graph as [`SiteMetadata`] with
+[`with_metadata`][ComponentGraph::with_metadata], so that the graph can be
+passed around as the single source of truth about a site.
+
+The operational state of the components, like open relays or tripped fuses,
+can be tracked in a [`StateOverlay`], which tells which components are still
+connected to the grid, without rebuilding the graph.
+
+For reviews, [`to_html`][ComponentGraph::to_html] renders the graph as a
+standalone, interactive HTML page, and
+[`to_plantuml`][ComponentGraph::to_plantuml] as a PlantUML diagram for
+documentation.
+For numerical tooling, the topology is available as an adjacency list from
+[`to_adjacency_list`][ComponentGraph::to_adjacency_list], or as a dense matrix
+from [`to_adjacency_matrix`][ComponentGraph::to_adjacency_matrix].
+
+Topologies that are valid but usually point to wrong site data, like PV
+inverters without a PV meter, can be found with
+[`lint`][ComponentGraph::lint].
 */
 
+pub mod builder;
+
 mod component_category;
-pub use component_category::{ComponentCategory, InverterType};
+pub use component_category::{ComponentCategory, EvChargerType, InverterType};
+
+mod config;
+pub use config::{ComponentGraphConfig, OtherCategoryPolicy};
 
 mod graph;
-pub use graph::{iterators, ComponentGraph};
+pub use graph::{
+    invariants, iterators, validate_components, ComponentGraph, Feeder, FeederRole, GraphSummary,
+    MeterRole, Quarantine, Reachability, ValidationReport,
+};
+
+mod phases;
+pub use phases::{Phase, Phases};
+
+mod site_metadata;
+pub use site_metadata::{Location, SiteMetadata};
+
+mod snapshot;
+pub use snapshot::{GraphChange, Snapshot};
+
+mod state_overlay;
+pub use state_overlay::StateOverlay;
+
+mod simple;
+pub use simple::{SimpleComponent, SimpleConnection};
+
+mod versioned;
+pub use versioned::VersionedGraph;
+
+#[cfg(feature = "python")]
+mod python;
+
+#[cfg(feature = "test-support")]
+pub mod random;
+
+#[cfg(feature = "proptest")]
+pub mod strategies;
 
 mod graph_traits;
 pub use graph_traits::{Edge, Node};
 
 mod error;
-pub use error::Error;
+pub use error::{Error, ErrorKind};
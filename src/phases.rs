@@ -0,0 +1,76 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! This module defines the `Phase` and `Phases` types, which describe the
+//! phases of the AC system that a connection carries.
+
+use std::fmt::Display;
+
+/// Represents one of the phases of a three-phase AC system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Phase {
+    L1,
+    L2,
+    L3,
+}
+
+impl Phase {
+    /// All the phases, in order.
+    pub const ALL: [Phase; 3] = [Phase::L1, Phase::L2, Phase::L3];
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::L1 => write!(f, "L1"),
+            Phase::L2 => write!(f, "L2"),
+            Phase::L3 => write!(f, "L3"),
+        }
+    }
+}
+
+/// Represents the phases carried by a connection, as returned by
+/// [`Edge::phases`][crate::Edge::phases].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Phases {
+    /// A single-phase connection, on the given phase.
+    Single(Phase),
+    /// A three-phase connection.
+    Three,
+}
+
+impl Phases {
+    /// Returns true if the connection carries the given `phase`.
+    pub fn contains(&self, phase: Phase) -> bool {
+        match self {
+            Phases::Single(single) => *single == phase,
+            Phases::Three => true,
+        }
+    }
+}
+
+impl Display for Phases {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phases::Single(phase) => write!(f, "{phase}"),
+            Phases::Three => write!(f, "L1, L2, L3"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phases() {
+        assert!(Phases::Single(Phase::L2).contains(Phase::L2));
+        assert!(!Phases::Single(Phase::L2).contains(Phase::L1));
+        assert!(Phase::ALL.iter().all(|&p| Phases::Three.contains(p)));
+
+        assert_eq!(Phases::Single(Phase::L3).to_string(), "L3");
+        assert_eq!(Phases::Three.to_string(), "L1, L2, L3");
+    }
+}
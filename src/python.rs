@@ -0,0 +1,198 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Python bindings for the [`ComponentGraph`], enabled with the `python`
+//! feature.
+//!
+//! Graphs are created from the JSON representation described in the
+//! documentation of `ComponentGraph::from_json`, and errors are raised as
+//! `ComponentGraphError` exceptions, with the `Display` representation of the
+//! [`Error`] as the message.
+
+use pyo3::{create_exception, exceptions::PyException, prelude::*};
+
+use crate::{ComponentGraph, ComponentGraphConfig, Error, SimpleComponent, SimpleConnection};
+
+create_exception!(
+    component_graph,
+    ComponentGraphError,
+    PyException,
+    "Raised when a component graph is invalid, or can't be queried."
+);
+
+impl From<Error> for PyErr {
+    fn from(err: Error) -> Self {
+        ComponentGraphError::new_err(err.to_string())
+    }
+}
+
+/// A validated graph of the components of a microgrid.
+#[pyclass(name = "ComponentGraph", module = "component_graph", frozen)]
+struct PyComponentGraph {
+    graph: ComponentGraph<SimpleComponent, SimpleConnection>,
+}
+
+#[pymethods]
+impl PyComponentGraph {
+    /// Creates a graph from its JSON representation.
+    ///
+    /// The optional `config` is the JSON representation of a
+    /// `ComponentGraphConfig`.
+    #[staticmethod]
+    #[pyo3(signature = (json, config = None))]
+    fn from_json(json: &str, config: Option<&str>) -> PyResult<Self> {
+        let config = match config {
+            Some(config) => serde_json::from_str::<ComponentGraphConfig>(config)
+                .map_err(|e| Error::serialization(format!("Unable to parse config: {e}")))?,
+            None => ComponentGraphConfig::default(),
+        };
+        Ok(Self {
+            graph: ComponentGraph::from_json_with_config(json, config)?,
+        })
+    }
+
+    /// Returns the JSON representation of the graph.
+    fn to_json(&self) -> PyResult<String> {
+        Ok(self.graph.to_json()?)
+    }
+
+    /// Returns the IDs of all components in the graph, in ascending order.
+    fn component_ids(&self) -> Vec<u64> {
+        let mut ids = self.graph.components().map(|c| c.id).collect::<Vec<_>>();
+        ids.sort();
+        ids
+    }
+
+    /// Returns the category of the given component, like `"SolarInverter"`.
+    fn category(&self, component_id: u64) -> PyResult<String> {
+        Ok(self.graph.component(component_id)?.category.to_string())
+    }
+
+    /// Returns the name of the given component, if it has one.
+    fn name(&self, component_id: u64) -> PyResult<Option<String>> {
+        Ok(self.graph.component(component_id)?.name.clone())
+    }
+
+    /// Returns the IDs of the predecessors of the given component, in
+    /// ascending order.
+    fn predecessors(&self, component_id: u64) -> PyResult<Vec<u64>> {
+        let mut ids = self
+            .graph
+            .predecessors(component_id)?
+            .map(|c| c.id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Returns the IDs of the successors of the given component, in ascending
+    /// order.
+    fn successors(&self, component_id: u64) -> PyResult<Vec<u64>> {
+        let mut ids = self
+            .graph
+            .successors(component_id)?
+            .map(|c| c.id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Returns the role of the given meter, like `"PV"`, or `None` if the
+    /// component is not a meter.
+    fn meter_role(&self, component_id: u64) -> PyResult<Option<String>> {
+        Ok(self
+            .graph
+            .meter_role(component_id)?
+            .map(|role| role.to_string()))
+    }
+
+    /// Returns the validation warnings for the graph.
+    fn warnings(&self) -> Vec<String> {
+        self.graph
+            .revalidate()
+            .warnings()
+            .iter()
+            .map(|w| w.to_string())
+            .collect()
+    }
+
+    /// Returns advisory findings about the graph.
+    fn lint(&self) -> Vec<String> {
+        self.graph.lint().iter().map(|f| f.to_string()).collect()
+    }
+}
+
+/// The `component_graph` Python module.
+#[pymodule]
+fn component_graph(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyComponentGraph>()?;
+    m.add(
+        "ComponentGraphError",
+        m.py().get_type::<ComponentGraphError>(),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::{py_run, types::PyDict};
+
+    #[test]
+    fn test_python_bindings() -> PyResult<()> {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "component_graph")?;
+            component_graph(&module)?;
+            let locals = PyDict::new(py);
+            locals.set_item("cg", module)?;
+
+            py_run!(
+                py,
+                *locals,
+                r#"
+graph = cg.ComponentGraph.from_json("""{
+    "components": [
+        { "id": 1, "category": "Grid" },
+        { "id": 2, "category": "Meter", "name": "PV meter" },
+        { "id": 3, "category": { "Inverter": "Solar" } },
+        { "id": 4, "category": { "Inverter": "Solar" } },
+        { "id": 5, "category": "Meter" }
+    ],
+    "connections": [
+        { "source": 1, "destination": 5 },
+        { "source": 5, "destination": 2 },
+        { "source": 2, "destination": 3 },
+        { "source": 2, "destination": 4 }
+    ]
+}""")
+assert graph.component_ids() == [1, 2, 3, 4, 5]
+assert graph.category(3) == "SolarInverter"
+assert graph.name(2) == "PV meter"
+assert graph.name(3) is None
+assert graph.successors(2) == [3, 4]
+assert graph.predecessors(3) == [2]
+assert graph.meter_role(2) == "PV"
+assert graph.meter_role(5) == "Grid"
+assert graph.meter_role(3) is None
+assert graph.warnings() == []
+assert graph.lint() == []
+assert cg.ComponentGraph.from_json(graph.to_json()).to_json() == graph.to_json()
+
+try:
+    graph.successors(9)
+    assert False
+except cg.ComponentGraphError as e:
+    assert str(e) == "ComponentNotFound: Component with id 9 not found."
+
+try:
+    cg.ComponentGraph.from_json("{}")
+    assert False
+except cg.ComponentGraphError as e:
+    assert str(e).startswith("Serialization: Unable to parse graph")
+"#
+            );
+            Ok(())
+        })
+    }
+}
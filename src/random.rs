@@ -0,0 +1,180 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! Generation of random, valid [`ComponentGraph`]s, for property testing.
+
+use rand::Rng;
+
+use crate::{
+    builder::{ComponentGraphBuilder, ComponentHandle},
+    ComponentGraph, Error, SimpleComponent, SimpleConnection,
+};
+
+/// The parameters of the graphs generated by [`random_graph`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RandomGraphParams {
+    /// The number of batteries.
+    ///
+    /// Batteries are grouped into battery chains of one or two batteries,
+    /// each with its own meter and one or two battery inverters.
+    pub batteries: usize,
+
+    /// The number of PV chains, each of which is a meter with one to three
+    /// PV inverters.
+    pub pv_chains: usize,
+
+    /// The number of EV chargers.
+    ///
+    /// EV chargers are grouped under meters, with one to three chargers
+    /// each.
+    pub ev_chargers: usize,
+
+    /// The maximum number of meters between the grid meter and the meter of
+    /// a chain.
+    pub max_depth: usize,
+}
+
+impl Default for RandomGraphParams {
+    fn default() -> Self {
+        Self {
+            batteries: 2,
+            pv_chains: 2,
+            ev_chargers: 2,
+            max_depth: 2,
+        }
+    }
+}
+
+/// Returns a random microgrid topology with the given `params`.
+///
+/// The graph has a grid component, a grid meter, and the battery, PV and EV
+/// charger chains below it, either directly or nested under intermediate
+/// meters.  The generated graphs are valid with the default
+/// [`ComponentGraphConfig`][crate::ComponentGraphConfig], so an error is only
+/// returned if there is a bug in the generator.
+///
+/// ```
+/// use component_graph::random::{random_graph, RandomGraphParams};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let graph = random_graph(&RandomGraphParams::default(), &mut rng)?;
+/// assert!(graph.revalidate().is_valid());
+/// # Ok::<(), component_graph::Error>(())
+/// ```
+pub fn random_graph(
+    params: &RandomGraphParams,
+    rng: &mut impl Rng,
+) -> Result<ComponentGraph<SimpleComponent, SimpleConnection>, Error> {
+    let mut builder = ComponentGraphBuilder::new();
+    let grid = builder.grid();
+    let grid_meter = builder.meter();
+    builder.connect(grid, grid_meter);
+
+    let mut parents = Parents {
+        meters: vec![(grid_meter, 0)],
+        max_depth: params.max_depth,
+    };
+
+    let mut batteries = params.batteries;
+    while batteries > 0 {
+        let num_batteries = rng.gen_range(1..=batteries.min(2));
+        batteries -= num_batteries;
+        let chain = builder.meter_bat_chain(rng.gen_range(1..=2), num_batteries);
+        parents.attach(&mut builder, chain, rng);
+    }
+
+    for _ in 0..params.pv_chains {
+        let chain = builder.meter_pv_chain(rng.gen_range(1..=3));
+        parents.attach(&mut builder, chain, rng);
+    }
+
+    let mut ev_chargers = params.ev_chargers;
+    while ev_chargers > 0 {
+        let num_chargers = rng.gen_range(1..=ev_chargers.min(3));
+        ev_chargers -= num_chargers;
+        let chain = builder.meter_ev_charger_chain(num_chargers);
+        parents.attach(&mut builder, chain, rng);
+    }
+
+    builder.build()
+}
+
+/// The meters that chains can be added under.
+struct Parents {
+    /// The meters, with their depth below the grid meter.
+    meters: Vec<(ComponentHandle, usize)>,
+    max_depth: usize,
+}
+
+impl Parents {
+    /// Connects the given `chain` to a random meter, sometimes through a new
+    /// intermediate meter.
+    fn attach(
+        &mut self,
+        builder: &mut ComponentGraphBuilder,
+        chain: ComponentHandle,
+        rng: &mut impl Rng,
+    ) {
+        let (mut parent, depth) = self.meters[rng.gen_range(0..self.meters.len())];
+        if depth < self.max_depth && rng.gen_bool(0.3) {
+            let meter = builder.meter();
+            builder.connect(parent, meter);
+            self.meters.push((meter, depth + 1));
+            parent = meter;
+        }
+        builder.connect(parent, chain);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{component_category::CategoryPredicates, MeterRole};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_random_graph() -> Result<(), Error> {
+        let params = RandomGraphParams {
+            batteries: 5,
+            pv_chains: 3,
+            ev_chargers: 4,
+            max_depth: 3,
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let graph = random_graph(&params, &mut rng)?;
+            let count = |predicate: fn(&SimpleComponent) -> bool| {
+                graph.components().filter(|c| predicate(c)).count()
+            };
+            assert_eq!(count(|c| c.is_battery()), 5);
+            assert_eq!(count(|c| c.is_ev_charger()), 4);
+            // Each PV chain has a PV meter, and the grid meter or intermediate
+            // meters are never PV meters.
+            assert_eq!(
+                graph
+                    .components()
+                    .filter(|c| graph.meter_role(c.id) == Ok(Some(MeterRole::Pv)))
+                    .filter(|c| graph
+                        .successors(c.id)
+                        .is_ok_and(|mut s| s.all(|s| !s.is_meter())))
+                    .count(),
+                3
+            );
+            assert!(graph.revalidate().is_valid());
+        }
+
+        let graph = random_graph(
+            &RandomGraphParams {
+                batteries: 0,
+                pv_chains: 0,
+                ev_chargers: 0,
+                max_depth: 0,
+            },
+            &mut rng,
+        )?;
+        assert_eq!(graph.components().count(), 2);
+
+        Ok(())
+    }
+}
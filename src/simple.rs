@@ -0,0 +1,237 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! This module defines the `SimpleComponent` and `SimpleConnection` structs,
+//! which are built-in implementations of the [`Node`] and [`Edge`] traits.
+
+use crate::{ComponentCategory, Edge, Node, Phases};
+
+/// A component that owns all its data.
+///
+/// Useful for tools and tests that don't have their own component type, and
+/// don't want to implement the [`Node`] trait.  Simple components are always
+/// supported.
+///
+/// With the `serde` feature, this is the representation of a component in the
+/// JSON schema used by `ComponentGraph::from_json` and
+/// `ComponentGraph::to_json`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimpleComponent {
+    /// The component id.
+    pub id: u64,
+    /// The category of the component.
+    pub category: ComponentCategory,
+    /// An optional human readable name for the component.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub name: Option<String>,
+    /// An optional external identifier for the component, like a UUID from
+    /// the cloud APIs.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub external_id: Option<String>,
+}
+
+impl SimpleComponent {
+    /// Creates a new component with the given id and category, and no name.
+    pub fn new(id: u64, category: ComponentCategory) -> Self {
+        Self {
+            id,
+            category,
+            name: None,
+            external_id: None,
+        }
+    }
+
+    /// Sets the name of the component.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the external identifier of the component.
+    pub fn with_external_id(mut self, external_id: impl Into<String>) -> Self {
+        self.external_id = Some(external_id.into());
+        self
+    }
+}
+
+impl Node for SimpleComponent {
+    fn component_id(&self) -> u64 {
+        self.id
+    }
+
+    fn category(&self) -> ComponentCategory {
+        self.category
+    }
+
+    fn is_supported(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn external_id(&self) -> Option<&str> {
+        self.external_id.as_deref()
+    }
+}
+
+/// A connection between two components.
+///
+/// Useful together with [`SimpleComponent`], for tools and tests that don't
+/// want to implement the [`Edge`] trait.
+///
+/// With the `serde` feature, this is the representation of a connection in
+/// the JSON schema used by `ComponentGraph::from_json` and
+/// `ComponentGraph::to_json`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimpleConnection {
+    /// The id of the source component.
+    pub source: u64,
+    /// The id of the destination component.
+    pub destination: u64,
+    /// The optional rated current of the connection in amperes.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub rated_current: Option<f64>,
+    /// The optional length of the cable of the connection in meters.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub cable_length: Option<f64>,
+    /// The optional phases carried by the connection.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub phases: Option<Phases>,
+}
+
+impl SimpleConnection {
+    /// Creates a new connection from `source` to `destination`, without
+    /// ratings.
+    pub fn new(source: u64, destination: u64) -> Self {
+        Self {
+            source,
+            destination,
+            rated_current: None,
+            cable_length: None,
+            phases: None,
+        }
+    }
+
+    /// Sets the rated current of the connection, in amperes.
+    pub fn with_rated_current(mut self, rated_current: f64) -> Self {
+        self.rated_current = Some(rated_current);
+        self
+    }
+
+    /// Sets the length of the cable of the connection, in meters.
+    pub fn with_cable_length(mut self, cable_length: f64) -> Self {
+        self.cable_length = Some(cable_length);
+        self
+    }
+
+    /// Sets the phases carried by the connection.
+    pub fn with_phases(mut self, phases: Phases) -> Self {
+        self.phases = Some(phases);
+        self
+    }
+}
+
+impl Edge for SimpleConnection {
+    fn source(&self) -> u64 {
+        self.source
+    }
+
+    fn destination(&self) -> u64 {
+        self.destination
+    }
+
+    fn rated_current(&self) -> Option<f64> {
+        self.rated_current
+    }
+
+    fn cable_length(&self) -> Option<f64> {
+        self.cable_length
+    }
+
+    fn phases(&self) -> Option<Phases> {
+        self.phases
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentGraph, Error, InverterType};
+
+    #[test]
+    fn test_simple_types() -> Result<(), Error> {
+        let graph = ComponentGraph::try_new(
+            vec![
+                SimpleComponent::new(1, ComponentCategory::Grid),
+                SimpleComponent::new(2, ComponentCategory::Meter).with_name("Main meter"),
+                SimpleComponent::new(3, ComponentCategory::Inverter(InverterType::Solar)),
+            ],
+            vec![SimpleConnection::new(1, 2), SimpleConnection::new(2, 3)],
+        )?;
+
+        let meter = graph.component(2)?;
+        assert_eq!(meter.name.as_deref(), Some("Main meter"));
+        assert!(meter.is_supported());
+        assert_eq!(graph.component(3)?.name, None);
+        assert_eq!(
+            graph
+                .successors(2)?
+                .map(|c| c.component_id())
+                .collect::<Vec<_>>(),
+            vec![3]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_external_ids() -> Result<(), Error> {
+        let components = vec![
+            SimpleComponent::new(1, ComponentCategory::Grid),
+            SimpleComponent::new(2, ComponentCategory::Meter).with_external_id("a1b2"),
+            SimpleComponent::new(3, ComponentCategory::Inverter(InverterType::Solar))
+                .with_external_id("c3d4"),
+        ];
+        let connections = vec![SimpleConnection::new(1, 2), SimpleConnection::new(2, 3)];
+        let graph = ComponentGraph::try_new(components.clone(), connections.clone())?;
+
+        assert_eq!(graph.component_by_external_id("a1b2")?.id, 2);
+        assert_eq!(graph.component_by_external_id("c3d4")?.id, 3);
+        assert_eq!(
+            graph.component_by_external_id("e5f6"),
+            Err(Error::component_not_found(
+                "Component with external id e5f6 not found."
+            ))
+        );
+
+        let mut duplicated = components;
+        duplicated[1].external_id = Some("c3d4".to_string());
+        assert!(
+            ComponentGraph::try_new(duplicated, connections).is_err_and(|e| {
+                e == Error::invalid_graph("Duplicate external ID found: c3d4")
+                    && e.component_ids() == [2, 3]
+            })
+        );
+
+        Ok(())
+    }
+}
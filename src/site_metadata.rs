@@ -0,0 +1,85 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! This module defines the `SiteMetadata` struct, which holds site-level
+//! information that can be attached to a [`ComponentGraph`][crate::ComponentGraph].
+
+use std::collections::BTreeMap;
+
+/// Site-level information about the microgrid that a
+/// [`ComponentGraph`][crate::ComponentGraph] describes.
+///
+/// None of the fields are used by the graph itself.  They are carried along
+/// with it, so that services can pass the graph around as the single source
+/// of truth about a site.
+///
+/// With the `serde` feature, the metadata can be serialized and
+/// deserialized.  Fields missing from the input take their default values.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct SiteMetadata {
+    /// The ID of the microgrid.
+    pub microgrid_id: Option<u64>,
+
+    /// The name of the site.
+    pub name: Option<String>,
+
+    /// The geographical location of the site.
+    pub location: Option<Location>,
+
+    /// The IANA name of the time zone of the site, e.g. `Europe/Berlin`.
+    pub timezone: Option<String>,
+
+    /// Any other information about the site, as key-value pairs.
+    pub extra: BTreeMap<String, String>,
+}
+
+/// The geographical location of a site.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location {
+    /// The latitude, in degrees.
+    pub latitude: f64,
+    /// The longitude, in degrees.
+    pub longitude: f64,
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize() -> Result<(), serde_json::Error> {
+        let metadata: SiteMetadata = serde_json::from_str("{}")?;
+        assert_eq!(metadata, SiteMetadata::default());
+
+        let metadata: SiteMetadata = serde_json::from_str(
+            r#"{
+                "microgrid_id": 12,
+                "location": { "latitude": 52.52, "longitude": 13.405 },
+                "extra": { "operator": "ACME" }
+            }"#,
+        )?;
+        assert_eq!(
+            metadata,
+            SiteMetadata {
+                microgrid_id: Some(12),
+                location: Some(Location {
+                    latitude: 52.52,
+                    longitude: 13.405,
+                }),
+                extra: BTreeMap::from([("operator".to_string(), "ACME".to_string())]),
+                ..Default::default()
+            }
+        );
+
+        let json = serde_json::to_string(&metadata)?;
+        assert_eq!(serde_json::from_str::<SiteMetadata>(&json)?, metadata);
+
+        Ok(())
+    }
+}
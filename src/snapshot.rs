@@ -0,0 +1,271 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! This module defines the `Snapshot` struct, which allows a
+//! [`ComponentGraph`] to be replaced while it is being read from other
+//! threads.
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::{ComponentGraph, Edge, Node};
+
+/// A callback registered with [`Snapshot::on_change`].
+type Observer<N, E> = Box<dyn FnMut(&GraphChange<'_, N, E>) + Send>;
+
+/// A shared, replaceable [`ComponentGraph`].
+///
+/// Readers get an [`Arc`] to the current graph with [`load`][Snapshot::load],
+/// and keep using it for as long as they need, even if a new graph is
+/// [`store`][Snapshot::store]d in the meantime.  This lets long-running
+/// services pick up changes to the component graph without stopping the
+/// readers.
+///
+/// Callbacks registered with [`on_change`][Snapshot::on_change] are called
+/// whenever a new graph is stored, so that caches derived from the graph can
+/// be invalidated.  Caches can also remember the version of the graph they
+/// were built from, with [`load_versioned`][Snapshot::load_versioned], and
+/// compare it with the current one.
+///
+/// A `ComponentGraph` is `Send` and `Sync` when its node and edge types are,
+/// and so is a `Snapshot` of it.
+pub struct Snapshot<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// The current graph, and its version.
+    current: RwLock<(Arc<ComponentGraph<N, E>>, u64)>,
+    observers: Mutex<Vec<Observer<N, E>>>,
+}
+
+/// A replacement of the graph in a [`Snapshot`], passed to the callbacks
+/// registered with [`on_change`][Snapshot::on_change], or between two
+/// versions of a [`VersionedGraph`][crate::VersionedGraph].
+pub struct GraphChange<'a, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// The graph that was replaced.
+    pub previous: &'a ComponentGraph<N, E>,
+    /// The graph that replaced it.
+    pub current: &'a ComponentGraph<N, E>,
+    /// The version of the graph that was replaced.
+    pub previous_version: u64,
+    /// The version of the graph that replaced it.
+    pub current_version: u64,
+}
+
+impl<N, E> GraphChange<'_, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Returns the IDs of the components that are in the new graph, but not
+    /// in the previous one.
+    pub fn added_components(&self) -> BTreeSet<u64> {
+        component_ids(self.current)
+            .difference(&component_ids(self.previous))
+            .copied()
+            .collect()
+    }
+
+    /// Returns the IDs of the components that were in the previous graph, but
+    /// not in the new one.
+    pub fn removed_components(&self) -> BTreeSet<u64> {
+        component_ids(self.previous)
+            .difference(&component_ids(self.current))
+            .copied()
+            .collect()
+    }
+}
+
+fn component_ids<N: Node, E: Edge>(graph: &ComponentGraph<N, E>) -> BTreeSet<u64> {
+    graph.components().map(|c| c.component_id()).collect()
+}
+
+impl<N, E> Snapshot<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Creates a new `Snapshot` holding the given graph.
+    pub fn new(graph: impl Into<Arc<ComponentGraph<N, E>>>) -> Self {
+        Self {
+            current: RwLock::new((graph.into(), 0)),
+            observers: Mutex::new(vec![]),
+        }
+    }
+
+    /// Returns the current graph.
+    pub fn load(&self) -> Arc<ComponentGraph<N, E>> {
+        self.load_versioned().0
+    }
+
+    /// Returns the current graph, together with its version.
+    ///
+    /// The version starts at `0`, and is increased by one with every
+    /// [`store`][Snapshot::store].  Both are read at once, so the version is
+    /// always the one of the returned graph, even if another thread stores a
+    /// new graph at the same time.
+    pub fn load_versioned(&self) -> (Arc<ComponentGraph<N, E>>, u64) {
+        // The lock is only held for cloning or replacing the `Arc`, which
+        // can't leave it in an inconsistent state, so a poisoned lock is
+        // still safe to use.
+        let current = self.current.read().unwrap_or_else(|e| e.into_inner());
+        (current.0.clone(), current.1)
+    }
+
+    /// Returns the version of the current graph.
+    ///
+    /// This is only advisory: another thread can store a new graph right
+    /// after the version is read, so it doesn't necessarily match the graph
+    /// returned by a following [`load`][Snapshot::load].  Use
+    /// [`load_versioned`][Snapshot::load_versioned] to get a graph together
+    /// with its version.
+    pub fn version(&self) -> u64 {
+        self.load_versioned().1
+    }
+
+    /// Replaces the current graph with the given one, and returns the
+    /// previous graph.
+    ///
+    /// Readers that loaded the previous graph keep using it, until they
+    /// load again.  The registered callbacks are called after the graph has
+    /// been replaced, in the order they were registered.
+    pub fn store(&self, graph: impl Into<Arc<ComponentGraph<N, E>>>) -> Arc<ComponentGraph<N, E>> {
+        // Holding the observers for the whole call makes concurrent stores
+        // notify in the order they replaced the graph.
+        let mut observers = self.observers.lock().unwrap_or_else(|e| e.into_inner());
+        let graph = graph.into();
+        let (previous, previous_version) = {
+            let mut current = self.current.write().unwrap_or_else(|e| e.into_inner());
+            let version = current.1 + 1;
+            std::mem::replace(&mut *current, (graph.clone(), version))
+        };
+        let change = GraphChange {
+            previous: &previous,
+            current: &graph,
+            previous_version,
+            current_version: previous_version + 1,
+        };
+        for observer in observers.iter_mut() {
+            observer(&change);
+        }
+        previous
+    }
+
+    /// Registers a callback to be called whenever a new graph is
+    /// [`store`][Snapshot::store]d.
+    ///
+    /// The callback can [`load`][Snapshot::load] from the snapshot, but must
+    /// not store into it or register other callbacks, which would deadlock.
+    pub fn on_change(&self, observer: impl FnMut(&GraphChange<'_, N, E>) + Send + 'static) {
+        self.observers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Box::new(observer));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentCategory, Error, SimpleComponent, SimpleConnection};
+
+    fn graph(meters: u64) -> Result<ComponentGraph<SimpleComponent, SimpleConnection>, Error> {
+        let mut components = vec![SimpleComponent::new(1, ComponentCategory::Grid)];
+        let mut connections = vec![];
+        for id in 2..meters + 2 {
+            components.push(SimpleComponent::new(id, ComponentCategory::Meter));
+            connections.push(SimpleConnection::new(1, id));
+        }
+        ComponentGraph::try_new(components, connections)
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ComponentGraph<SimpleComponent, SimpleConnection>>();
+        assert_send_sync::<Snapshot<SimpleComponent, SimpleConnection>>();
+    }
+
+    #[test]
+    fn test_snapshot() -> Result<(), Error> {
+        let snapshot = Arc::new(Snapshot::new(graph(1)?));
+
+        let old = snapshot.load();
+        assert_eq!(old.components().count(), 2);
+
+        let reader = {
+            let snapshot = snapshot.clone();
+            std::thread::spawn(move || snapshot.load().components().count())
+        };
+        let previous = snapshot.store(graph(2)?);
+        assert!(Arc::ptr_eq(&previous, &old));
+        let (current, version) = snapshot.load_versioned();
+        assert_eq!((current.components().count(), version), (3, 1));
+
+        // The old graph is still usable, while new loads see the new graph.
+        assert_eq!(old.components().count(), 2);
+        assert_eq!(snapshot.load().components().count(), 3);
+        assert!(matches!(reader.join(), Ok(2 | 3)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_versioned() -> Result<(), Error> {
+        // The graph of version `v` has `v + 2` components.
+        let snapshot = Arc::new(Snapshot::new(graph(1)?));
+        let writer = {
+            let snapshot = snapshot.clone();
+            std::thread::spawn(move || -> Result<(), Error> {
+                for meters in 2..50 {
+                    snapshot.store(graph(meters)?);
+                }
+                Ok(())
+            })
+        };
+        for _ in 0..1000 {
+            let (current, version) = snapshot.load_versioned();
+            assert_eq!(current.components().count() as u64, version + 2);
+        }
+        assert!(matches!(writer.join(), Ok(Ok(()))));
+        assert_eq!(snapshot.version(), 48);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_on_change() -> Result<(), Error> {
+        let snapshot = Snapshot::new(graph(2)?);
+        let changes = Arc::new(Mutex::new(vec![]));
+        {
+            let changes = changes.clone();
+            snapshot.on_change(move |change| {
+                if let Ok(mut changes) = changes.lock() {
+                    changes.push((
+                        change.current_version,
+                        change.added_components(),
+                        change.removed_components(),
+                    ));
+                }
+            });
+        }
+
+        snapshot.store(graph(3)?);
+        snapshot.store(graph(1)?);
+        assert_eq!(snapshot.version(), 2);
+        assert_eq!(
+            *changes.lock().unwrap_or_else(|e| e.into_inner()),
+            vec![
+                (1, BTreeSet::from([4]), BTreeSet::new()),
+                (2, BTreeSet::new(), BTreeSet::from([3, 4])),
+            ]
+        );
+
+        Ok(())
+    }
+}
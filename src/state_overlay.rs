@@ -0,0 +1,235 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! This module defines the `StateOverlay` struct, which tracks the
+//! operational state of the components in a [`ComponentGraph`].
+
+use std::collections::{BTreeSet, HashSet};
+use std::sync::OnceLock;
+
+use crate::{ComponentGraph, Edge, Error, Node};
+
+/// The operational state of the components of a [`ComponentGraph`].
+///
+/// Components can be marked as offline, for example when a relay is open or
+/// a fuse has tripped, without rebuilding the graph.  The overlay then
+/// answers which components are still energized, i.e., connected to the
+/// grid through online components only.
+///
+/// All components are online when the overlay is created.
+///
+/// The energized components are found with a single walk down from the grid,
+/// the first time they are needed after a state change, so many
+/// [`is_energized`][StateOverlay::is_energized] queries for the same state
+/// are cheap.
+pub struct StateOverlay<'a, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    cg: &'a ComponentGraph<N, E>,
+    offline: HashSet<u64>,
+    /// The IDs of the energized components, computed the first time they
+    /// are needed, and cleared when a component changes state.
+    energized: OnceLock<BTreeSet<u64>>,
+}
+
+impl<'a, N, E> StateOverlay<'a, N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Creates a new `StateOverlay` for the given graph, with all components
+    /// online.
+    pub fn new(cg: &'a ComponentGraph<N, E>) -> Self {
+        Self {
+            cg,
+            offline: HashSet::new(),
+            energized: OnceLock::new(),
+        }
+    }
+
+    /// Returns the graph the overlay is for.
+    pub fn graph(&self) -> &'a ComponentGraph<N, E> {
+        self.cg
+    }
+
+    /// Marks the component with the given `component_id` as offline.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn set_offline(&mut self, component_id: u64) -> Result<(), Error> {
+        self.cg.component(component_id)?;
+        if self.offline.insert(component_id) {
+            self.energized = OnceLock::new();
+        }
+        Ok(())
+    }
+
+    /// Marks the component with the given `component_id` as online.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn set_online(&mut self, component_id: u64) -> Result<(), Error> {
+        self.cg.component(component_id)?;
+        if self.offline.remove(&component_id) {
+            self.energized = OnceLock::new();
+        }
+        Ok(())
+    }
+
+    /// Returns true if the component with the given `component_id` is
+    /// online.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn is_online(&self, component_id: u64) -> Result<bool, Error> {
+        self.cg.component(component_id)?;
+        Ok(!self.offline.contains(&component_id))
+    }
+
+    /// Returns true if the component with the given `component_id` is online
+    /// and connected to the grid through online components only.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn is_energized(&self, component_id: u64) -> Result<bool, Error> {
+        self.cg.component(component_id)?;
+        Ok(self.energized_ids().contains(&component_id))
+    }
+
+    /// Returns an iterator over the energized components, in the order of
+    /// their component IDs.
+    pub fn energized_components(&self) -> impl Iterator<Item = &'a N> {
+        let cg = self.cg;
+        self.energized_ids()
+            .clone()
+            .into_iter()
+            .filter_map(move |id| cg.component(id).ok())
+    }
+
+    /// Returns an iterator over the online *successors* of the component with
+    /// the given `component_id`.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn successors(&self, component_id: u64) -> Result<impl Iterator<Item = &'a N> + '_, Error> {
+        Ok(self
+            .cg
+            .successors(component_id)?
+            .filter(|n| !self.offline.contains(&n.component_id())))
+    }
+
+    /// Returns an iterator over the online *predecessors* of the component
+    /// with the given `component_id`.
+    ///
+    /// Returns an error if the given `component_id` does not exist.
+    pub fn predecessors(
+        &self,
+        component_id: u64,
+    ) -> Result<impl Iterator<Item = &'a N> + '_, Error> {
+        Ok(self
+            .cg
+            .predecessors(component_id)?
+            .filter(|n| !self.offline.contains(&n.component_id())))
+    }
+
+    /// Returns the IDs of the components that can be reached from the grid
+    /// through online components, computing them if needed.
+    fn energized_ids(&self) -> &BTreeSet<u64> {
+        self.energized.get_or_init(|| self.find_energized_ids())
+    }
+
+    /// Finds the IDs of the components that can be reached from the grid
+    /// through online components.
+    fn find_energized_ids(&self) -> BTreeSet<u64> {
+        let mut energized = BTreeSet::new();
+        let root_id = self.cg.root_id();
+        if self.offline.contains(&root_id) {
+            return energized;
+        }
+        let mut stack = vec![root_id];
+        energized.insert(root_id);
+        while let Some(id) = stack.pop() {
+            let Ok(successors) = self.successors(id) else {
+                continue;
+            };
+            for successor in successors {
+                if energized.insert(successor.component_id()) {
+                    stack.push(successor.component_id());
+                }
+            }
+        }
+        energized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentCategory, InverterType, SimpleComponent, SimpleConnection};
+
+    fn graph() -> Result<ComponentGraph<SimpleComponent, SimpleConnection>, Error> {
+        ComponentGraph::try_new(
+            vec![
+                SimpleComponent::new(1, ComponentCategory::Grid),
+                SimpleComponent::new(2, ComponentCategory::Meter),
+                SimpleComponent::new(3, ComponentCategory::Meter),
+                SimpleComponent::new(4, ComponentCategory::Inverter(InverterType::Solar)),
+                SimpleComponent::new(5, ComponentCategory::Meter),
+                SimpleComponent::new(6, ComponentCategory::Inverter(InverterType::Battery)),
+                SimpleComponent::new(7, ComponentCategory::Battery),
+            ],
+            vec![
+                SimpleConnection::new(1, 2),
+                SimpleConnection::new(2, 3),
+                SimpleConnection::new(3, 4),
+                SimpleConnection::new(2, 5),
+                SimpleConnection::new(5, 6),
+                SimpleConnection::new(6, 7),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_state_overlay() -> Result<(), Error> {
+        let graph = graph()?;
+        let mut overlay = StateOverlay::new(&graph);
+        let energized = |overlay: &StateOverlay<SimpleComponent, SimpleConnection>| {
+            overlay
+                .energized_components()
+                .map(|c| c.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(energized(&overlay), vec![1, 2, 3, 4, 5, 6, 7]);
+
+        // Taking the PV meter offline disconnects the PV inverter.
+        overlay.set_offline(3)?;
+        assert!(!overlay.is_online(3)?);
+        assert!(overlay.is_online(4)?);
+        assert!(!overlay.is_energized(4)?);
+        assert_eq!(energized(&overlay), vec![1, 2, 5, 6, 7]);
+        assert_eq!(
+            overlay.successors(2)?.map(|c| c.id).collect::<Vec<_>>(),
+            vec![5]
+        );
+        assert_eq!(overlay.predecessors(4)?.count(), 0);
+
+        overlay.set_offline(6)?;
+        assert_eq!(energized(&overlay), vec![1, 2, 5]);
+        // Marking a component that is already offline keeps the cached
+        // state.
+        overlay.set_offline(6)?;
+        assert!(overlay.energized.get().is_some());
+        assert!(!overlay.is_energized(7)?);
+
+        overlay.set_online(3)?;
+        assert_eq!(energized(&overlay), vec![1, 2, 3, 4, 5]);
+
+        // The underlying graph is unchanged.
+        assert_eq!(overlay.graph().successors(2)?.count(), 2);
+
+        assert_eq!(
+            overlay.set_offline(9),
+            Err(Error::component_not_found("Component with id 9 not found."))
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! `proptest` strategies for component categories, built-in components and
+//! whole graphs.
+//!
+//! [`ComponentCategory`], [`InverterType`], [`EvChargerType`],
+//! [`SimpleComponent`] and [`SimpleConnection`] implement
+//! [`Arbitrary`], so they can be generated with [`any`].  [`valid_graph`]
+//! generates valid graphs, and [`graph_parts`] generates components and
+//! connections that are mostly invalid, for fuzzing validation.
+
+use proptest::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    random::{random_graph, RandomGraphParams},
+    ComponentCategory, ComponentGraph, EvChargerType, InverterType, SimpleComponent,
+    SimpleConnection,
+};
+
+impl Arbitrary for InverterType {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            Just(InverterType::Unspecified),
+            Just(InverterType::Solar),
+            Just(InverterType::Battery),
+            Just(InverterType::Hybrid),
+            Just(InverterType::Wind),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for EvChargerType {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            Just(EvChargerType::Unspecified),
+            Just(EvChargerType::Ac),
+            Just(EvChargerType::Dc),
+            Just(EvChargerType::Hybrid),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for ComponentCategory {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            Just(ComponentCategory::Unspecified),
+            Just(ComponentCategory::Grid),
+            Just(ComponentCategory::Meter),
+            Just(ComponentCategory::Battery),
+            any::<InverterType>().prop_map(ComponentCategory::Inverter),
+            any::<EvChargerType>().prop_map(ComponentCategory::EvCharger),
+            Just(ComponentCategory::Converter),
+            Just(ComponentCategory::CryptoMiner),
+            Just(ComponentCategory::Electrolyzer),
+            Just(ComponentCategory::Chp),
+            Just(ComponentCategory::Precharger),
+            Just(ComponentCategory::Fuse),
+            Just(ComponentCategory::VoltageTransformer),
+            Just(ComponentCategory::Hvac),
+            Just(ComponentCategory::Relay),
+            Just(ComponentCategory::Sensor),
+            Just(ComponentCategory::PvString),
+            Just(ComponentCategory::BatteryModule),
+            any::<u32>().prop_map(ComponentCategory::Other),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for SimpleComponent {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<u64>(), any::<ComponentCategory>())
+            .prop_map(|(id, category)| SimpleComponent::new(id, category))
+            .boxed()
+    }
+}
+
+impl Arbitrary for SimpleConnection {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<u64>(), any::<u64>())
+            .prop_map(|(source, destination)| SimpleConnection::new(source, destination))
+            .boxed()
+    }
+}
+
+/// Returns a strategy for valid graphs, with up to `max_batteries`
+/// batteries, `max_pv_chains` PV chains and `max_ev_chargers` EV chargers.
+///
+/// The graphs are generated with [`random_graph`], with up to two levels of
+/// intermediate meters.
+pub fn valid_graph(
+    max_batteries: usize,
+    max_pv_chains: usize,
+    max_ev_chargers: usize,
+) -> impl Strategy<Value = ComponentGraph<SimpleComponent, SimpleConnection>> {
+    (
+        0..=max_batteries,
+        0..=max_pv_chains,
+        0..=max_ev_chargers,
+        0..=2usize,
+        any::<u64>(),
+    )
+        .prop_filter_map(
+            "the generated graph should be valid",
+            |(batteries, pv_chains, ev_chargers, max_depth, seed)| {
+                let params = RandomGraphParams {
+                    batteries,
+                    pv_chains,
+                    ev_chargers,
+                    max_depth,
+                };
+                random_graph(&params, &mut StdRng::seed_from_u64(seed)).ok()
+            },
+        )
+}
+
+/// Returns a strategy for up to `max_components` components with IDs
+/// starting from `1`, and random connections between them.
+///
+/// The components have arbitrary categories, and some of the connections
+/// point to missing components, so most of the results don't form a valid
+/// graph.
+pub fn graph_parts(
+    max_components: usize,
+) -> impl Strategy<Value = (Vec<SimpleComponent>, Vec<SimpleConnection>)> {
+    prop::collection::vec(any::<ComponentCategory>(), 1..=max_components.max(1)).prop_flat_map(
+        |categories| {
+            let count = categories.len() as u64;
+            let components = categories
+                .into_iter()
+                .zip(1..)
+                .map(|(category, id)| SimpleComponent::new(id, category))
+                .collect::<Vec<_>>();
+            let connections = prop::collection::vec(
+                (1..=count + 1, 1..=count + 1)
+                    .prop_map(|(source, destination)| SimpleConnection::new(source, destination)),
+                0..=2 * count as usize,
+            );
+            (Just(components), connections)
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{validate_components, ComponentGraphConfig};
+
+    proptest! {
+        #[test]
+        fn test_valid_graph(graph in valid_graph(4, 3, 4)) {
+            prop_assert!(graph.revalidate().is_valid());
+            prop_assert!(graph.lint().iter().all(|e| e.rule() != Some("pv_meter")));
+        }
+
+        #[test]
+        fn test_graph_parts((components, connections) in graph_parts(8)) {
+            let report = validate_components(
+                components.clone(),
+                connections.clone(),
+                ComponentGraphConfig::default(),
+            );
+            let graph = ComponentGraph::try_new(components, connections);
+            prop_assert_eq!(report.is_valid(), graph.is_ok());
+        }
+
+        #[test]
+        fn test_arbitrary_category(category in any::<ComponentCategory>()) {
+            prop_assert_eq!(category.to_string().parse::<ComponentCategory>(), Ok(category));
+        }
+    }
+}
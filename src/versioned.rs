@@ -0,0 +1,150 @@
+// License: MIT
+// Copyright © 2024 Frequenz Energy-as-a-Service GmbH
+
+//! This module defines the `VersionedGraph` struct, which pairs a
+//! [`ComponentGraph`] with a version that is increased whenever the graph is
+//! changed.
+
+use std::time::SystemTime;
+
+use crate::{ComponentGraph, Edge, Error, GraphChange, Node};
+
+/// A [`ComponentGraph`] with a version and the time of its last change.
+///
+/// The version starts at `0` and is increased by one by every method that
+/// changes the graph, so that caches derived from the graph can detect that
+/// they are stale by comparing versions, without comparing the graphs.
+pub struct VersionedGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    graph: ComponentGraph<N, E>,
+    version: u64,
+    modified_at: SystemTime,
+}
+
+impl<N, E> VersionedGraph<N, E>
+where
+    N: Node,
+    E: Edge,
+{
+    /// Creates a new `VersionedGraph` holding the given graph, at version
+    /// `0`.
+    pub fn new(graph: ComponentGraph<N, E>) -> Self {
+        Self {
+            graph,
+            version: 0,
+            modified_at: SystemTime::now(),
+        }
+    }
+
+    /// Returns the current graph.
+    pub fn graph(&self) -> &ComponentGraph<N, E> {
+        &self.graph
+    }
+
+    /// Returns the version of the current graph.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the time the graph was created or last changed.
+    pub fn modified_at(&self) -> SystemTime {
+        self.modified_at
+    }
+
+    /// Replaces the graph with the given one, increasing the version, and
+    /// returns the previous graph with its version.
+    pub fn replace(&mut self, graph: ComponentGraph<N, E>) -> Self {
+        let next = Self {
+            graph,
+            version: self.version + 1,
+            modified_at: SystemTime::now(),
+        };
+        std::mem::replace(self, next)
+    }
+
+    /// Returns the change from the `previous` versioned graph to this one.
+    pub fn diff<'a>(&'a self, previous: &'a Self) -> GraphChange<'a, N, E> {
+        GraphChange {
+            previous: &previous.graph,
+            current: &self.graph,
+            previous_version: previous.version,
+            current_version: self.version,
+        }
+    }
+
+    /// Consumes the `VersionedGraph`, returning the graph.
+    pub fn into_inner(self) -> ComponentGraph<N, E> {
+        self.graph
+    }
+}
+
+impl<N, E> VersionedGraph<N, E>
+where
+    N: Node + Clone,
+    E: Edge + Clone,
+{
+    /// Removes the components with the given IDs, like
+    /// [`ComponentGraph::without`], increasing the version, and returns the
+    /// previous graph with its version.
+    ///
+    /// Returns an error, and leaves the graph unchanged, if the resulting
+    /// graph is invalid.
+    pub fn remove_components(
+        &mut self,
+        component_ids: impl IntoIterator<Item = u64>,
+    ) -> Result<Self, Error> {
+        let graph = self.graph.without(component_ids)?;
+        Ok(self.replace(graph))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::{ComponentCategory, SimpleComponent, SimpleConnection};
+
+    fn graph(meters: u64) -> Result<ComponentGraph<SimpleComponent, SimpleConnection>, Error> {
+        let mut components = vec![SimpleComponent::new(1, ComponentCategory::Grid)];
+        let mut connections = vec![];
+        for id in 2..meters + 2 {
+            components.push(SimpleComponent::new(id, ComponentCategory::Meter));
+            connections.push(SimpleConnection::new(1, id));
+        }
+        ComponentGraph::try_new(components, connections)
+    }
+
+    #[test]
+    fn test_versioned_graph() -> Result<(), Error> {
+        let mut versioned = VersionedGraph::new(graph(1)?);
+        assert_eq!(versioned.version(), 0);
+        let created_at = versioned.modified_at();
+
+        let previous = versioned.replace(graph(3)?);
+        assert_eq!(previous.version(), 0);
+        assert_eq!(versioned.version(), 1);
+        assert!(versioned.modified_at() >= created_at);
+
+        let change = versioned.diff(&previous);
+        assert_eq!((change.previous_version, change.current_version), (0, 1));
+        assert_eq!(change.added_components(), BTreeSet::from([3, 4]));
+
+        let previous = versioned.remove_components([4])?;
+        assert_eq!(versioned.version(), 2);
+        assert_eq!(
+            versioned.diff(&previous).removed_components(),
+            BTreeSet::from([4])
+        );
+
+        // Failed changes keep the graph and its version.
+        assert!(versioned.remove_components([9]).is_err());
+        assert_eq!(versioned.version(), 2);
+        assert_eq!(versioned.into_inner().components().count(), 3);
+
+        Ok(())
+    }
+}